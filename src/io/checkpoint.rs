@@ -0,0 +1,388 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Lines, Write};
+use std::path::Path;
+
+use super::ProgramInfo;
+
+const CHECKPOINT_VERSION: &str = "2";
+
+/// Everything needed to restore one in-memory process's live PCB and its
+/// backing memory region: the process's static shape (via `ProgramInfo`),
+/// its CPU-visible state at the moment of the checkpoint, and the
+/// accumulated scheduling timers `SchedulerStats` reports on.
+pub struct ProcessSnapshot {
+    pub program_info: ProgramInfo,
+    pub mem_start_address: usize,
+    pub mem_end_address: usize,
+    pub program_counter: usize,
+    pub registers: [u32; 16],
+    /// `0` = Ready, `1` = Running, `2` = Waiting, `3` = Terminated,
+    /// `4` = Faulted, `5` = SystemCall, `6` = Preempted, `7` = Breakpoint;
+    /// kept as a plain tag here since `io` doesn't depend on
+    /// `kernel::ProcessState` (see `ProcessState::as_tag`/`from_tag`).
+    pub state_tag: u8,
+    pub turnaround_time_ms: f64,
+    pub burst_times_ms: Vec<f64>,
+    pub wait_time_ms: f64,
+    pub io_wait_time_ms: f64,
+    pub context_switch_count: u32,
+    pub io_request_count: u32,
+    pub enqueued_at_ns: u128,
+    pub instructions_executed_count: u64,
+    pub data: Vec<u32>,
+}
+
+/// Which queue each checkpointed process id was sitting in, so the
+/// schedulers can be rebuilt with the same work still pending.
+pub struct SchedulerSnapshot {
+    pub ready_ids: Vec<u32>,
+    pub waiting_ids: Vec<u32>,
+    pub pending_ids: Vec<u32>,
+}
+
+pub struct Checkpoint {
+    pub disk_programs: Vec<(ProgramInfo, Vec<u32>)>,
+    pub processes: Vec<ProcessSnapshot>,
+    pub scheduler: SchedulerSnapshot,
+}
+
+/// Writes a full simulator checkpoint: the disk's program catalog, every
+/// resident process's memory/PCB state, and the scheduler queues, all in
+/// the same self-describing hex-literal style as `core_dump`. Reused
+/// between runs via `restore_checkpoint` so a partially executed batch can
+/// resume from exactly where it stopped.
+pub fn write_checkpoint(path: &str, checkpoint: &Checkpoint) -> io::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut file = File::create(path)?;
+
+    writeln!(file, "// CHECKPOINT v{}", CHECKPOINT_VERSION)?;
+
+    writeln!(file, "// DISK {:X}", checkpoint.disk_programs.len())?;
+    for (program_info, data) in &checkpoint.disk_programs {
+        write_program_block(&mut file, program_info, data)?;
+    }
+
+    writeln!(file, "// MEMORY {:X}", checkpoint.processes.len())?;
+    for process in &checkpoint.processes {
+        writeln!(
+            file,
+            "// PROC {:X} {:X} {:X} {:X} {:X} {:X}",
+            process.program_info.id,
+            process.program_info.priority,
+            process.mem_start_address,
+            process.mem_end_address,
+            process.program_counter,
+            process.state_tag,
+        )?;
+        writeln!(
+            file,
+            "// REGS {}",
+            process.registers.iter().map(|r| format!("{:X}", r)).collect::<Vec<_>>().join(" "),
+        )?;
+        writeln!(
+            file,
+            "// TIMERS {:X} {:X} {:X} {:X} {:X} {:X} {:X}",
+            process.turnaround_time_ms.to_bits(),
+            process.wait_time_ms.to_bits(),
+            process.io_wait_time_ms.to_bits(),
+            process.context_switch_count,
+            process.io_request_count,
+            process.enqueued_at_ns,
+            process.instructions_executed_count,
+        )?;
+        writeln!(file, "// BURSTS {:X}", process.burst_times_ms.len())?;
+        for burst_time_ms in &process.burst_times_ms {
+            writeln!(file, "{:X}", burst_time_ms.to_bits())?;
+        }
+        write_program_block(&mut file, &process.program_info, &process.data)?;
+    }
+
+    writeln!(file, "// READY {}", join_hex(&checkpoint.scheduler.ready_ids))?;
+    writeln!(file, "// WAITING {}", join_hex(&checkpoint.scheduler.waiting_ids))?;
+    writeln!(file, "// PENDING {}", join_hex(&checkpoint.scheduler.pending_ids))?;
+
+    Ok(())
+}
+
+fn join_hex(ids: &[u32]) -> String {
+    ids.iter().map(|id| format!("{:X}", id)).collect::<Vec<_>>().join(" ")
+}
+
+/// Same block format `core_dump::write_core_dump` uses for a single
+/// program: a `JOB` header, instruction words, a `Data` header, then the
+/// remaining buffer words.
+fn write_program_block(file: &mut File, program_info: &ProgramInfo, data: &[u32]) -> io::Result<()> {
+    writeln!(file, "// JOB {:X} {:X} {:X}", program_info.id, program_info.instruction_buffer_size, program_info.priority)?;
+
+    for word in &data[0..program_info.instruction_buffer_size] {
+        writeln!(file, "0x{:08X}", word)?;
+    }
+
+    writeln!(file, "// Data {:X} {:X} {:X}", program_info.in_buffer_size, program_info.out_buffer_size, program_info.temp_buffer_size)?;
+
+    for word in &data[program_info.instruction_buffer_size..] {
+        writeln!(file, "0x{:08X}", word)?;
+    }
+
+    writeln!(file, "// END")?;
+
+    Ok(())
+}
+
+/// Parses a checkpoint produced by `write_checkpoint` back into its parts,
+/// ready to rebuild a `Disk`, `Memory`, and the scheduler queues from.
+pub fn restore_checkpoint(path: &str) -> io::Result<Checkpoint> {
+    let file = File::open(path)?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header = next_line(&mut lines)?;
+    if !header.starts_with("// CHECKPOINT") {
+        return Err(invalid_data("Missing checkpoint header"));
+    }
+
+    let disk_count = parse_count(&next_line(&mut lines)?, "// DISK")?;
+    let mut disk_programs = Vec::with_capacity(disk_count);
+    for _ in 0..disk_count {
+        disk_programs.push(read_program_block(&mut lines)?);
+    }
+
+    let memory_count = parse_count(&next_line(&mut lines)?, "// MEMORY")?;
+    let mut processes = Vec::with_capacity(memory_count);
+    for _ in 0..memory_count {
+        processes.push(read_process_snapshot(&mut lines)?);
+    }
+
+    let ready_ids = parse_id_list(&next_line(&mut lines)?, "// READY")?;
+    let waiting_ids = parse_id_list(&next_line(&mut lines)?, "// WAITING")?;
+    let pending_ids = parse_id_list(&next_line(&mut lines)?, "// PENDING")?;
+
+    Ok(Checkpoint {
+        disk_programs,
+        processes,
+        scheduler: SchedulerSnapshot { ready_ids, waiting_ids, pending_ids },
+    })
+}
+
+fn next_line(lines: &mut Lines<BufReader<File>>) -> io::Result<String> {
+    lines.next().ok_or_else(|| invalid_data("Unexpected end of checkpoint file"))?
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn parse_count(line: &str, tag: &str) -> io::Result<usize> {
+    let rest = line.strip_prefix(tag).ok_or_else(|| invalid_data("Malformed checkpoint section header"))?;
+    usize::from_str_radix(rest.trim(), 16).map_err(|_| invalid_data("Malformed checkpoint count"))
+}
+
+fn parse_id_list(line: &str, tag: &str) -> io::Result<Vec<u32>> {
+    let rest = line.strip_prefix(tag).ok_or_else(|| invalid_data("Malformed checkpoint queue header"))?;
+
+    rest.split_whitespace()
+        .map(|token| u32::from_str_radix(token, 16).map_err(|_| invalid_data("Malformed checkpoint process id")))
+        .collect()
+}
+
+fn parse_hex_word(line: &str) -> io::Result<u32> {
+    let line = line.trim();
+    u32::from_str_radix(&line[2..], 16).map_err(|_| invalid_data("Malformed hex word"))
+}
+
+fn read_program_block(lines: &mut Lines<BufReader<File>>) -> io::Result<(ProgramInfo, Vec<u32>)> {
+    let job_line = next_line(lines)?;
+    let job_fields: Vec<&str> = job_line.strip_prefix("// JOB ").ok_or_else(|| invalid_data("Expected JOB header"))?.split_whitespace().collect();
+
+    let id = u32::from_str_radix(job_fields[0], 16).map_err(|_| invalid_data("Malformed job id"))?;
+    let instruction_buffer_size = usize::from_str_radix(job_fields[1], 16).map_err(|_| invalid_data("Malformed instruction buffer size"))?;
+    let priority = u32::from_str_radix(job_fields[2], 16).map_err(|_| invalid_data("Malformed priority"))?;
+
+    let mut data = Vec::with_capacity(instruction_buffer_size);
+    for _ in 0..instruction_buffer_size {
+        data.push(parse_hex_word(&next_line(lines)?)?);
+    }
+
+    let data_line = next_line(lines)?;
+    let data_fields: Vec<&str> = data_line.strip_prefix("// Data ").ok_or_else(|| invalid_data("Expected Data header"))?.split_whitespace().collect();
+
+    let in_buffer_size = usize::from_str_radix(data_fields[0], 16).map_err(|_| invalid_data("Malformed in buffer size"))?;
+    let out_buffer_size = usize::from_str_radix(data_fields[1], 16).map_err(|_| invalid_data("Malformed out buffer size"))?;
+    let temp_buffer_size = usize::from_str_radix(data_fields[2], 16).map_err(|_| invalid_data("Malformed temp buffer size"))?;
+
+    for _ in 0..(in_buffer_size + out_buffer_size + temp_buffer_size) {
+        data.push(parse_hex_word(&next_line(lines)?)?);
+    }
+
+    let end_line = next_line(lines)?;
+    if end_line.trim() != "// END" {
+        return Err(invalid_data("Expected END marker"));
+    }
+
+    Ok((
+        ProgramInfo {
+            id,
+            priority,
+            instruction_buffer_size,
+            in_buffer_size,
+            out_buffer_size,
+            temp_buffer_size,
+            data_start_idx: 0,
+        },
+        data,
+    ))
+}
+
+fn read_process_snapshot(lines: &mut Lines<BufReader<File>>) -> io::Result<ProcessSnapshot> {
+    let proc_line = next_line(lines)?;
+    let proc_fields: Vec<&str> = proc_line.strip_prefix("// PROC ").ok_or_else(|| invalid_data("Expected PROC header"))?.split_whitespace().collect();
+
+    let priority = u32::from_str_radix(proc_fields[1], 16).map_err(|_| invalid_data("Malformed priority"))?;
+    let mem_start_address = usize::from_str_radix(proc_fields[2], 16).map_err(|_| invalid_data("Malformed mem start address"))?;
+    let mem_end_address = usize::from_str_radix(proc_fields[3], 16).map_err(|_| invalid_data("Malformed mem end address"))?;
+    let program_counter = usize::from_str_radix(proc_fields[4], 16).map_err(|_| invalid_data("Malformed program counter"))?;
+    let state_tag = u8::from_str_radix(proc_fields[5], 16).map_err(|_| invalid_data("Malformed state tag"))?;
+
+    let regs_line = next_line(lines)?;
+    let reg_fields: Vec<&str> = regs_line.strip_prefix("// REGS ").ok_or_else(|| invalid_data("Expected REGS header"))?.split_whitespace().collect();
+
+    if reg_fields.len() != 16 {
+        return Err(invalid_data("Expected 16 registers"));
+    }
+
+    let mut registers = [0u32; 16];
+    for (i, field) in reg_fields.iter().enumerate() {
+        registers[i] = u32::from_str_radix(field, 16).map_err(|_| invalid_data("Malformed register value"))?;
+    }
+
+    let timers_line = next_line(lines)?;
+    let timer_fields: Vec<&str> = timers_line.strip_prefix("// TIMERS ").ok_or_else(|| invalid_data("Expected TIMERS header"))?.split_whitespace().collect();
+
+    let turnaround_time_ms = f64::from_bits(u64::from_str_radix(timer_fields[0], 16).map_err(|_| invalid_data("Malformed turnaround time"))?);
+    let wait_time_ms = f64::from_bits(u64::from_str_radix(timer_fields[1], 16).map_err(|_| invalid_data("Malformed wait time"))?);
+    let io_wait_time_ms = f64::from_bits(u64::from_str_radix(timer_fields[2], 16).map_err(|_| invalid_data("Malformed io wait time"))?);
+    let context_switch_count = u32::from_str_radix(timer_fields[3], 16).map_err(|_| invalid_data("Malformed context switch count"))?;
+    let io_request_count = u32::from_str_radix(timer_fields[4], 16).map_err(|_| invalid_data("Malformed io request count"))?;
+    let enqueued_at_ns = u128::from_str_radix(timer_fields[5], 16).map_err(|_| invalid_data("Malformed enqueued at timestamp"))?;
+    let instructions_executed_count = u64::from_str_radix(timer_fields[6], 16).map_err(|_| invalid_data("Malformed instructions executed count"))?;
+
+    let burst_count = parse_count(&next_line(lines)?, "// BURSTS")?;
+    let mut burst_times_ms = Vec::with_capacity(burst_count);
+    for _ in 0..burst_count {
+        let burst_line = next_line(lines)?;
+        let bits = u64::from_str_radix(burst_line.trim(), 16).map_err(|_| invalid_data("Malformed burst time"))?;
+        burst_times_ms.push(f64::from_bits(bits));
+    }
+
+    let (program_info, data) = read_program_block(lines)?;
+
+    Ok(ProcessSnapshot {
+        program_info: ProgramInfo { priority, ..program_info },
+        mem_start_address,
+        mem_end_address,
+        program_counter,
+        registers,
+        state_tag,
+        turnaround_time_ms,
+        burst_times_ms,
+        wait_time_ms,
+        io_wait_time_ms,
+        context_switch_count,
+        io_request_count,
+        enqueued_at_ns,
+        instructions_executed_count,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    #[test]
+    fn test_write_then_restore_checkpoint() {
+        let path = "test_checkpoint.tmp";
+
+        let disk_program_info = ProgramInfo {
+            id: 9,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+        let process_program_info = ProgramInfo {
+            id: 1,
+            priority: 2,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        let mut registers = [0u32; 16];
+        registers[2] = 0x2A;
+
+        let checkpoint = Checkpoint {
+            disk_programs: vec![(disk_program_info, vec![0x92000000, 0x00000005])],
+            processes: vec![ProcessSnapshot {
+                program_info: process_program_info,
+                mem_start_address: 0,
+                mem_end_address: 3,
+                program_counter: 1,
+                registers,
+                state_tag: 0,
+                turnaround_time_ms: 12.5,
+                burst_times_ms: vec![1.0, 2.5],
+                wait_time_ms: 3.25,
+                io_wait_time_ms: 0.0,
+                context_switch_count: 2,
+                io_request_count: 1,
+                enqueued_at_ns: 123456789,
+                instructions_executed_count: 42,
+                data: vec![0x92000000, 0x00000007, 0x00000000],
+            }],
+            scheduler: SchedulerSnapshot {
+                ready_ids: vec![2, 3],
+                waiting_ids: vec![4],
+                pending_ids: vec![5, 6],
+            },
+        };
+
+        write_checkpoint(path, &checkpoint).unwrap();
+        let restored = restore_checkpoint(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(restored.disk_programs.len(), 1);
+        assert_eq!(restored.disk_programs[0].0.id, 9);
+        assert_eq!(restored.disk_programs[0].1, vec![0x92000000, 0x00000005]);
+
+        assert_eq!(restored.processes.len(), 1);
+        let process = &restored.processes[0];
+        assert_eq!(process.program_info.id, 1);
+        assert_eq!(process.mem_start_address, 0);
+        assert_eq!(process.mem_end_address, 3);
+        assert_eq!(process.program_counter, 1);
+        assert_eq!(process.registers[2], 0x2A);
+        assert_eq!(process.turnaround_time_ms, 12.5);
+        assert_eq!(process.burst_times_ms, vec![1.0, 2.5]);
+        assert_eq!(process.wait_time_ms, 3.25);
+        assert_eq!(process.context_switch_count, 2);
+        assert_eq!(process.io_request_count, 1);
+        assert_eq!(process.enqueued_at_ns, 123456789);
+        assert_eq!(process.instructions_executed_count, 42);
+        assert_eq!(process.data, vec![0x92000000, 0x00000007, 0x00000000]);
+
+        assert_eq!(restored.scheduler.ready_ids, vec![2, 3]);
+        assert_eq!(restored.scheduler.waiting_ids, vec![4]);
+        assert_eq!(restored.scheduler.pending_ids, vec![5, 6]);
+    }
+}
@@ -0,0 +1,185 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+
+/// Where `Driver::new` looks for settings by default. Absent entirely or
+/// missing individual keys, `Config::load` falls back to the matching
+/// `DEFAULT_*` constant below so a run with no config file behaves exactly
+/// like it did before this module existed.
+pub const DEFAULT_CONFIG_PATH: &str = "config.txt";
+
+const DEFAULT_NUM_CORES: usize = 2;
+const DEFAULT_MEMORY_SIZE: usize = 1024;
+const DEFAULT_PROGRAM_FILE_PATH: &str = "data/program_file.txt";
+const DEFAULT_OUT_PATH: &str = "out";
+const DEFAULT_CHECKPOINT_INTERVAL_BATCHES: Option<usize> = None;
+
+/// Plain mirror of `kernel::StsSchedulingAlg`. `io` doesn't depend on
+/// `kernel` (see `io::checkpoint`'s own state tags for the same reason),
+/// so `Driver::new` is the one that converts this into the real enum.
+#[derive(Debug, PartialEq)]
+pub enum SchedulingAlgConfig {
+    Fifo,
+    Priority { aging_interval_ms: u128 },
+    RoundRobin { quantum: usize },
+}
+
+/// Scheduler, memory, and core parameters read from a settings file at
+/// startup, with `Driver::new` building its `LongTermScheduler`/
+/// `ShortTermScheduler`/`Memory` from these instead of hardcoded literals.
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub scheduling_alg: SchedulingAlgConfig,
+    pub num_cores: usize,
+    pub memory_size: usize,
+    pub program_file_path: String,
+    pub out_path: String,
+    /// Write a `Driver::checkpoint` after every Nth completed batch when
+    /// `Some`. `None` (the default) never checkpoints, matching how a run
+    /// behaved before `checkpoint`/`restore` existed.
+    pub checkpoint_interval_batches: Option<usize>,
+}
+
+impl Config {
+    pub fn default() -> Config {
+        Config {
+            scheduling_alg: SchedulingAlgConfig::Fifo,
+            num_cores: DEFAULT_NUM_CORES,
+            memory_size: DEFAULT_MEMORY_SIZE,
+            program_file_path: DEFAULT_PROGRAM_FILE_PATH.to_string(),
+            out_path: DEFAULT_OUT_PATH.to_string(),
+            checkpoint_interval_batches: DEFAULT_CHECKPOINT_INTERVAL_BATCHES,
+        }
+    }
+
+    /// Reads `key = value` settings from `path`. Falls back to
+    /// `Config::default()` wholesale if the file doesn't exist, and to that
+    /// same default's value for any individual setting the file doesn't
+    /// mention, so existing runs keep working whether or not they've
+    /// adopted a config file yet.
+    pub fn load(path: &str) -> io::Result<Config> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(err),
+        };
+
+        let mut config = Config::default();
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            let value = value.trim();
+
+            match key {
+                "scheduling_alg" => config.scheduling_alg = Config::parse_scheduling_alg(value),
+                "num_cores" => if let Ok(value) = value.parse() {
+                    config.num_cores = value;
+                },
+                "memory_size" => if let Ok(value) = value.parse() {
+                    config.memory_size = value;
+                },
+                "program_file_path" => config.program_file_path = value.to_string(),
+                "out_path" => config.out_path = value.to_string(),
+                "checkpoint_interval_batches" => if let Ok(value) = value.parse() {
+                    config.checkpoint_interval_batches = Some(value);
+                },
+                _ => {},
+            }
+        }
+
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// `fifo`, `priority <aging_interval_ms>`, or `round_robin <quantum>`.
+    /// Falls back to `Fifo` for anything unrecognized rather than failing
+    /// the whole load over one bad line.
+    fn parse_scheduling_alg(value: &str) -> SchedulingAlgConfig {
+        let parts: Vec<&str> = value.split_whitespace().collect();
+
+        match parts.as_slice() {
+            ["fifo"] => SchedulingAlgConfig::Fifo,
+            ["priority", aging_interval_ms] => SchedulingAlgConfig::Priority {
+                aging_interval_ms: aging_interval_ms.parse().unwrap_or(0),
+            },
+            ["round_robin", quantum] => SchedulingAlgConfig::RoundRobin {
+                quantum: quantum.parse().unwrap_or(1),
+            },
+            _ => SchedulingAlgConfig::Fifo,
+        }
+    }
+
+    /// Rejects settings `Driver` could never run with. Memory size is
+    /// validated separately once the program file is loaded (see
+    /// `Driver::start`), since the largest job size isn't known here.
+    fn validate(&self) -> io::Result<()> {
+        if self.num_cores == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "num_cores must be at least 1"));
+        }
+
+        if self.memory_size == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "memory_size must be at least 1"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_load_missing_file_returns_default() {
+        let config = Config::load("test_config_missing.tmp").unwrap();
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_config_load_parses_settings_and_falls_back_for_missing_keys() {
+        let path = "test_config_load.tmp";
+        std::fs::write(path, "scheduling_alg = round_robin 5\nnum_cores = 4\n# a comment\nmemory_size = 2048\n").unwrap();
+
+        let config = Config::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(config.scheduling_alg, SchedulingAlgConfig::RoundRobin { quantum: 5 });
+        assert_eq!(config.num_cores, 4);
+        assert_eq!(config.memory_size, 2048);
+        assert_eq!(config.program_file_path, DEFAULT_PROGRAM_FILE_PATH);
+        assert_eq!(config.out_path, DEFAULT_OUT_PATH);
+        assert_eq!(config.checkpoint_interval_batches, None);
+    }
+
+    #[test]
+    fn test_config_load_parses_checkpoint_interval_batches() {
+        let path = "test_config_load_checkpoint_interval.tmp";
+        std::fs::write(path, "checkpoint_interval_batches = 3\n").unwrap();
+
+        let config = Config::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(config.checkpoint_interval_batches, Some(3));
+    }
+
+    #[test]
+    fn test_config_load_rejects_zero_cores() {
+        let path = "test_config_zero_cores.tmp";
+        std::fs::write(path, "num_cores = 0\n").unwrap();
+
+        let result = Config::load(path);
+        std::fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+    }
+}
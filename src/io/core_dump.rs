@@ -1,18 +1,161 @@
-use std::fs::File;
-use std::io::prelude::*;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
 
-fn coredump(memory : Vecdeque<u32>) -> std::io::Result<()> {
-    let mut file = File::create("core dump");
+use super::ProgramInfo;
 
-    //write the jobs
-    file.write("JOB")
-    while memory != 0 & memory.len() > 15 {
-    file.write(memory.pop_front());
+/// Writes a self-describing core dump: for each resident process, a `JOB`
+/// header (id, instruction buffer size, priority), the instruction words,
+/// a `Data` header (in/out/temp buffer sizes), and the remaining buffer
+/// words, each word as a fixed-width hex literal. This is the same
+/// convention `Loader::parse_job` and the program file already use, so a
+/// dump can be inspected or fed back in with `restore_core_dump`.
+pub fn write_core_dump(path: &str, programs: &[(ProgramInfo, Vec<u32>)]) -> io::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
     }
-    
-    //write the data
-    file.write("DATA")
-    while memory != 0 {
-        file.write(memory.pop_front());
+
+    let mut file = File::create(path)?;
+
+    for (program_info, data) in programs {
+        writeln!(file, "// JOB {:X} {:X} {:X}", program_info.id, program_info.instruction_buffer_size, program_info.priority)?;
+
+        for word in &data[0..program_info.instruction_buffer_size] {
+            writeln!(file, "0x{:08X}", word)?;
+        }
+
+        writeln!(file, "// Data {:X} {:X} {:X}", program_info.in_buffer_size, program_info.out_buffer_size, program_info.temp_buffer_size)?;
+
+        for word in &data[program_info.instruction_buffer_size..] {
+            writeln!(file, "0x{:08X}", word)?;
+        }
+
+        writeln!(file, "// END")?;
+    }
+
+    Ok(())
+}
+
+/// Parses a core dump produced by `write_core_dump` back into its
+/// `(ProgramInfo, data)` pairs, ready to be reloaded into a `Disk` via
+/// `Disk::write_program` or restored directly into `Memory`. Only caller is
+/// `Disk::restore_from` -- see its doc comment for why nothing reaches it
+/// from `Driver`.
+#[allow(dead_code)]
+pub fn restore_core_dump(path: &str) -> io::Result<Vec<(ProgramInfo, Vec<u32>)>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+
+    let mut programs = Vec::new();
+
+    let mut id = 0;
+    let mut priority = 0;
+    let mut instruction_buffer_size = 0;
+    let mut in_buffer_size = 0;
+    let mut out_buffer_size = 0;
+    let mut temp_buffer_size = 0;
+    let mut data = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+
+        if let Some(job_info) = line.strip_prefix("// JOB") {
+            let job_info: Vec<&str> = job_info.split_whitespace().collect();
+            if job_info.len() != 3 {
+                return Err(invalid_data("Malformed JOB header"));
+            }
+
+            id = u32::from_str_radix(job_info[0], 16).map_err(|_| invalid_data("Malformed job id"))?;
+            instruction_buffer_size = usize::from_str_radix(job_info[1], 16).map_err(|_| invalid_data("Malformed instruction buffer size"))?;
+            priority = u32::from_str_radix(job_info[2], 16).map_err(|_| invalid_data("Malformed priority"))?;
+        } else if let Some(data_info) = line.strip_prefix("// Data") {
+            let data_info: Vec<&str> = data_info.split_whitespace().collect();
+            if data_info.len() != 3 {
+                return Err(invalid_data("Malformed Data header"));
+            }
+
+            in_buffer_size = usize::from_str_radix(data_info[0], 16).map_err(|_| invalid_data("Malformed in buffer size"))?;
+            out_buffer_size = usize::from_str_radix(data_info[1], 16).map_err(|_| invalid_data("Malformed out buffer size"))?;
+            temp_buffer_size = usize::from_str_radix(data_info[2], 16).map_err(|_| invalid_data("Malformed temp buffer size"))?;
+        } else if line.starts_with("// END") {
+            programs.push((
+                ProgramInfo {
+                    id,
+                    priority,
+                    instruction_buffer_size,
+                    in_buffer_size,
+                    out_buffer_size,
+                    temp_buffer_size,
+                    data_start_idx: 0,
+                },
+                data.clone(),
+            ));
+
+            data.clear();
+        } else {
+            let line = line.trim();
+            if line.len() < 2 {
+                return Err(invalid_data("Malformed hex word"));
+            }
+
+            let value = u32::from_str_radix(&line[2..], 16).map_err(|_| invalid_data("Malformed hex word"))?;
+
+            data.push(value);
+        }
+    }
+
+    Ok(programs)
+}
+
+#[allow(dead_code)]
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    #[test]
+    fn test_write_then_restore_core_dump() {
+        let path = "test_core_dump.tmp";
+        let program_info = ProgramInfo {
+            id: 7,
+            priority: 3,
+            instruction_buffer_size: 2,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 1,
+            data_start_idx: 0,
+        };
+        let data = vec![0xC0500070, 0x92000000, 0x0000000A, 0x00000000, 0x00000000];
+
+        write_core_dump(path, &[(program_info, data.clone())]).unwrap();
+        let restored = restore_core_dump(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].0.id, 7);
+        assert_eq!(restored[0].0.priority, 3);
+        assert_eq!(restored[0].0.instruction_buffer_size, 2);
+        assert_eq!(restored[0].0.in_buffer_size, 1);
+        assert_eq!(restored[0].0.out_buffer_size, 1);
+        assert_eq!(restored[0].0.temp_buffer_size, 1);
+        assert_eq!(restored[0].1, data);
+    }
+
+    #[test]
+    fn test_restore_core_dump_malformed_hex_word_returns_err() {
+        let path = "test_core_dump_malformed.tmp";
+        fs::write(path, "// JOB 7 1 3\n0xNOTHEX\n// Data 0 0 0\n// END\n").unwrap();
+
+        let result = restore_core_dump(path);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(result.err().unwrap().kind(), io::ErrorKind::InvalidData);
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use super::ProgramInfo;
+use super::core_dump;
 
 const DISK_SIZE: usize = 4096;
 
@@ -33,14 +34,7 @@ impl Disk {
         &self.data[data_start_idx..data_end_idx]
     }
 
-    pub fn write_program(&mut self,
-                         id: u32,
-                         priority: u32,
-                         instruction_buffer_size: usize,
-                         in_buffer_size: usize,
-                         out_buffer_size: usize,
-                         temp_buffer_size: usize,
-                         data: &[u32]) {
+    pub fn write_program(&mut self, mut program_info: ProgramInfo, data: &[u32]) {
         let data_start_idx = self.current_data_idx;
         let data_end_idx = data_start_idx + data.len();
 
@@ -51,18 +45,39 @@ impl Disk {
         self.data[data_start_idx..data_end_idx].copy_from_slice(data);
         self.current_data_idx += data.len();
 
-        let program_info = ProgramInfo {
-            id,
-            priority,
-            instruction_buffer_size,
-            in_buffer_size,
-            out_buffer_size,
-            temp_buffer_size,
-            data_start_idx
-        };
-        
+        program_info.data_start_idx = data_start_idx;
+        let id = program_info.id;
+
         self.program_map.insert(id, program_info);
     }
+
+    /// Rebuilds a `Disk` from a core dump written by `core_dump::write_core_dump`.
+    /// Not wired into `Driver` -- `Driver::restore` resumes a suspended run via
+    /// `io::checkpoint` instead, which captures live process state a core dump
+    /// doesn't; kept for parity with `write_core_dump` (still used by
+    /// `LongTermScheduler::unload_all`) and exercised by its own test below.
+    #[allow(dead_code)]
+    pub fn restore_from(path: &str) -> std::io::Result<Disk> {
+        let mut disk = Disk::new();
+
+        for (program_info, data) in core_dump::restore_core_dump(path)? {
+            disk.write_program(program_info, &data);
+        }
+
+        Ok(disk)
+    }
+
+    /// Every program currently cataloged on disk, in id order, as
+    /// `(ProgramInfo, data)` pairs. Used by `io::checkpoint` to persist
+    /// the disk's full catalog alongside whatever's resident in memory.
+    pub fn all_programs(&self) -> Vec<(ProgramInfo, Vec<u32>)> {
+        let mut programs: Vec<(ProgramInfo, Vec<u32>)> = self.program_map.values()
+            .map(|program_info| (program_info.clone(), self.read_data_for(program_info).to_vec()))
+            .collect();
+
+        programs.sort_by_key(|(program_info, _)| program_info.id);
+        programs
+    }
 }
 
 #[cfg(test)]
@@ -72,7 +87,7 @@ mod tests {
     #[test]
     fn test_disk_write_program_then_read_data_for() {
         let mut disk = Disk::new();
-        disk.write_program(0, 0, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(0, 0, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
 
         let data = disk.read_data_for(disk.get_info_for(0));
         assert_eq!(data, &[1, 2, 3, 4, 5]);
@@ -89,6 +104,38 @@ mod tests {
     #[should_panic]
     fn test_disk_out_of_bounds_write_program() {
         let mut disk = Disk::new();
-        disk.write_program(0, 0, 0, 0, 0, 0, &[0; DISK_SIZE + 1]);
+        disk.write_program(ProgramInfo::new(0, 0, 0, 0, 0, 0), &[0; DISK_SIZE + 1]);
+    }
+
+    #[test]
+    fn test_disk_restore_from_core_dump() {
+        let path = "test_disk_restore_from.tmp";
+        let mut disk = Disk::new();
+        disk.write_program(ProgramInfo::new(1, 2, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
+
+        let program_info = disk.get_info_for(1).clone();
+        let data = disk.read_data_for(&program_info).to_vec();
+        core_dump::write_core_dump(path, &[(program_info, data)]).unwrap();
+
+        let restored = Disk::restore_from(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let data = restored.read_data_for(restored.get_info_for(1));
+        assert_eq!(data, &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_disk_all_programs() {
+        let mut disk = Disk::new();
+        disk.write_program(ProgramInfo::new(2, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(1, 1, 1, 1, 1, 1), &[6, 7, 8, 9]);
+
+        let programs = disk.all_programs();
+
+        assert_eq!(programs.len(), 2);
+        assert_eq!(programs[0].0.id, 1);
+        assert_eq!(programs[0].1, vec![6, 7, 8, 9]);
+        assert_eq!(programs[1].0.id, 2);
+        assert_eq!(programs[1].1, vec![1, 2, 3, 4, 5]);
     }
 }
\ No newline at end of file
@@ -1,14 +1,10 @@
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 
-use super::Disk;
+use super::{Disk, ProgramInfo};
 
-const PROGRAM_FILE_PATH: &str = "data/program_file.txt";
-const OUT_PATH: &str = "out";
-
-pub fn load_programs_into_disk(disk: &mut Disk) -> std::io::Result<Vec<u32>> {
-    let file = File::open(PROGRAM_FILE_PATH)?;
+pub fn load_programs_into_disk(disk: &mut Disk, program_file_path: &str) -> std::io::Result<Vec<u32>> {
+    let file = File::open(program_file_path)?;
     let reader = BufReader::new(file);
 
     let mut program_ids = Vec::new();
@@ -39,13 +35,10 @@ pub fn load_programs_into_disk(disk: &mut Disk) -> std::io::Result<Vec<u32>> {
             out_buffer_size = usize::from_str_radix(data_info[1], 16).unwrap();
             temp_buffer_size = usize::from_str_radix(data_info[2], 16).unwrap();
         } else if line.starts_with("// END") {
-            disk.write_program(id,
-                               priority,
-                               instruction_buffer_size,
-                               in_buffer_size,
-                               out_buffer_size,
-                               temp_buffer_size,
-                               data.as_slice());
+            disk.write_program(
+                ProgramInfo::new(id, priority, instruction_buffer_size, in_buffer_size, out_buffer_size, temp_buffer_size),
+                data.as_slice(),
+            );
 
             program_ids.push(id);
             data.clear();
@@ -62,40 +55,6 @@ pub fn load_programs_into_disk(disk: &mut Disk) -> std::io::Result<Vec<u32>> {
     Ok(program_ids)
 }
 
-pub fn write_disk_to_file(disk: &Disk) {
-    if !Path::new(OUT_PATH).exists() {
-        fs::create_dir(OUT_PATH).unwrap();
-    }
-
-    let filename = format!("{}/program_file_executed.txt", OUT_PATH);
-    let mut file = File::create(filename).unwrap();
-
-    let program_infos = disk.get_program_infos(true);
-
-    for program_info in program_infos {
-        let data = disk.read_data_for(&program_info);
-
-        writeln!(file, "// JOB {:X} {:X} {:X}", program_info.id, program_info.instruction_buffer_size, program_info.priority).unwrap();
-
-        for i in 0..program_info.instruction_buffer_size {
-            writeln!(file, "0x{:08X}", data[i]).unwrap();
-        }
-
-        writeln!(file, "// Data {:X} {:X} {:X}", program_info.in_buffer_size, program_info.out_buffer_size, program_info.temp_buffer_size).unwrap();
-
-        let start_idx = program_info.instruction_buffer_size;
-        let end_idx = start_idx
-                             + program_info.in_buffer_size
-                             + program_info.out_buffer_size
-                             + program_info.temp_buffer_size;
-        for i in start_idx..end_idx {
-            writeln!(file, "0x{:08X}", data[i]).unwrap();
-        }
-
-        writeln!(file, "// END").unwrap();
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,7 +62,7 @@ mod tests {
     #[test]
     fn test_load_programs_into_disk() {
         let mut disk = Disk::new();
-        load_programs_into_disk(&mut disk).unwrap();
+        load_programs_into_disk(&mut disk, "data/program_file.txt").unwrap();
 
         let program = disk.get_info_for(1);
 
@@ -1,7 +1,10 @@
+pub mod checkpoint;
+pub mod config;
+pub mod core_dump;
 pub mod disk;
 pub mod loader;
 pub mod program_info;
-pub mod disk_to_file;
+pub mod stats_report;
 
 pub use disk::Disk;
 pub use program_info::ProgramInfo;
\ No newline at end of file
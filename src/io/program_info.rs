@@ -1,3 +1,4 @@
+#[derive(Clone)]
 pub struct ProgramInfo {
     pub id: u32,
     pub priority: u32,
@@ -6,4 +7,28 @@ pub struct ProgramInfo {
     pub out_buffer_size: usize,
     pub temp_buffer_size: usize,
     pub data_start_idx: usize
+}
+
+impl ProgramInfo {
+    /// `data_start_idx` is wherever `Disk::write_program` ends up placing
+    /// the program's data, which isn't known until write time -- callers
+    /// building a `ProgramInfo` to pass in only know the other six fields.
+    pub fn new(
+        id: u32,
+        priority: u32,
+        instruction_buffer_size: usize,
+        in_buffer_size: usize,
+        out_buffer_size: usize,
+        temp_buffer_size: usize,
+    ) -> ProgramInfo {
+        ProgramInfo {
+            id,
+            priority,
+            instruction_buffer_size,
+            in_buffer_size,
+            out_buffer_size,
+            temp_buffer_size,
+            data_start_idx: 0,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Appends a scheduler statistics report to `path`, creating the file (and
+/// its parent directory) if needed. Appending rather than overwriting lets
+/// successive runs under different `StsSchedulingAlg` variants accumulate
+/// into one file so their reports can be compared side by side.
+pub fn append_report(path: &str, report: &str) -> io::Result<()> {
+    if let Some(parent) = Path::new(path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+
+    write!(file, "{}", report)
+}
@@ -1,18 +1,46 @@
+use std::collections::HashSet;
 use std::sync::{Arc, Condvar, Mutex, RwLock, atomic::{AtomicBool, Ordering}, mpsc};
 use std::thread;
 use std::time::Duration;
 
-use super::{Memory, ProcessControlBlock, ProcessState};
+use super::{Flags, Memory, MemoryFault, ProcessControlBlock, ProcessState};
+use super::disassemble;
+
+/// A handler for one `(instr_type, opcode)` slot in `CpuResources::opcode_table`
+/// -- see `Cpu::register_opcode`.
+pub(crate) type OpcodeHandler = fn(&mut CpuResources, &DecodedInstruction);
+
+/// `instr_type` is 2 bits and `opcode` is 6 bits, so the table has room for
+/// every combination the current instruction encoding can produce.
+const OPCODE_TABLE_LEN: usize = 256;
 
 pub(crate) struct Cpu {
     resources: Arc<Mutex<CpuResources>>,
     cycle_should_terminate: Arc<AtomicBool>,
+    cycle_thread_handle: Option<thread::JoinHandle<()>>,
     dma_should_terminate: Arc<AtomicBool>,
     dma_channel_handle: Option<thread::JoinHandle<()>>,
+    io_completion_receiver: mpsc::Receiver<u32>,
 }
 
 impl Cpu {
     pub fn new(memory: Arc<RwLock<Memory>>) -> Cpu {
+        Cpu::new_with_trace(memory, false)
+    }
+
+    /// Like `Cpu::new`, but logs a trace line to stderr after every
+    /// executed instruction: the program counter it was fetched from, its
+    /// disassembly (see `disassemble::disassemble`), and the register file
+    /// before and after, mirroring how 68000/ARM emulators expose
+    /// per-instruction disassembly for debugging a failing job. Not wired
+    /// into `Driver` -- meant to be called by hand for a one-off debugging
+    /// session instead of every run.
+    #[allow(dead_code)]
+    pub fn with_trace(memory: Arc<RwLock<Memory>>) -> Cpu {
+        Cpu::new_with_trace(memory, true)
+    }
+
+    fn new_with_trace(memory: Arc<RwLock<Memory>>, trace: bool) -> Cpu {
         let dma_should_terminate = Arc::new(AtomicBool::new(false));
 
         let memory_clone = memory.clone();
@@ -20,60 +48,118 @@ impl Cpu {
 
         // DMA thread.
         let (dma_sender, dma_receiver) = mpsc::channel();
+        let (io_completion_sender, io_completion_receiver) = mpsc::channel();
+
+        let resources = Arc::new(Mutex::new(CpuResources::new(memory, dma_sender, trace)));
+        let resources_clone = resources.clone();
 
         let dma_channel_handle = thread::spawn(move || {
             while !dma_should_terminate_clone.load(Ordering::Relaxed) {
                 if let Ok(command) = dma_receiver.recv_timeout(Duration::from_millis(100)) {
                     match command {
-                        DmaCommand::Fetch { address, response_sender } => {
-                            let memory_clone = memory_clone.read().unwrap();
-                            let value = memory_clone.read_from(address);
-                            response_sender.send(value).unwrap();
+                        DmaCommand::IoFetch { address, process_id, reg_num } => {
+                            let value = {
+                                let memory_clone = memory_clone.read().unwrap();
+                                memory_clone.read_from(address)
+                            };
+
+                            // `execute_process`'s out_pcb write-back copies
+                            // `CpuResources::registers` over whatever's in the
+                            // PCB wholesale, so writing straight to the PCB
+                            // here would be lost if that write-back hasn't
+                            // happened yet by the time this completes (this
+                            // thread isn't ordered against it by anything).
+                            // Writing into the live `resources.registers`
+                            // instead -- while this is still the process the
+                            // core has dispatched -- makes the eventual
+                            // write-back pick up this value instead of
+                            // clobbering it. Once the process has been
+                            // written back and parked (e.g. in `waiting_queue`),
+                            // `current_process_id` has moved on and the PCB
+                            // is the only copy left to update.
+                            let mut resources_clone = resources_clone.lock().unwrap();
+                            if resources_clone.current_process_id == process_id {
+                                resources_clone.registers[reg_num] = value;
+                            } else {
+                                drop(resources_clone);
+
+                                let memory_clone = memory_clone.read().unwrap();
+                                let pcb = memory_clone.get_pcb_for(process_id);
+                                pcb.lock().unwrap().registers[reg_num] = value;
+                            }
+
+                            io_completion_sender.send(process_id).unwrap();
                         },
-                        DmaCommand::Store { address, value, response_sender } => {
+                        DmaCommand::IoStore { address, value, process_id } => {
                             let mut memory_clone = memory_clone.write().unwrap();
                             memory_clone.write_to(address, value);
-                            response_sender.send(()).unwrap();
+                            drop(memory_clone);
+
+                            io_completion_sender.send(process_id).unwrap();
                         },
                     }
                 }
             }
         });
 
-        let resources = Arc::new(Mutex::new(CpuResources::new(memory, dma_sender)));
         let cycle_should_terminate = Arc::new(AtomicBool::new(false));
-        
+
         let resources_clone = resources.clone();
         let cycle_should_terminate_clone = cycle_should_terminate.clone();
 
         // CPU thread.
-        thread::spawn(move || {
+        let cycle_thread_handle = thread::spawn(move || {
             while !cycle_should_terminate_clone.load(Ordering::Relaxed) {
-                Cpu::cycle(&resources_clone);
+                Cpu::cycle(&resources_clone, &cycle_should_terminate_clone);
             }
         });
 
         Cpu {
             resources,
             cycle_should_terminate,
+            cycle_thread_handle: Some(cycle_thread_handle),
             dma_should_terminate,
             dma_channel_handle: Some(dma_channel_handle),
+            io_completion_receiver,
         }
     }
 
-    pub fn execute_process(&mut self, in_pcb: Option<Arc<Mutex<ProcessControlBlock>>>, out_pcb: Option<Arc<Mutex<ProcessControlBlock>>>) {
+    /// Drains the ids of processes whose queued DMA I/O request (`RD`/`WR`)
+    /// has completed since the last call, so the scheduler can move them
+    /// from the waiting queue back onto the ready queue.
+    pub fn take_completed_io(&self) -> Vec<u32> {
+        self.io_completion_receiver.try_iter().collect()
+    }
+
+    /// `quantum`, when set, bounds the next process to at most that many
+    /// instructions before `cycle` preempts it with `ProcessState::Preempted`,
+    /// enabling round-robin time slicing (see `StsSchedulingAlg::RoundRobin`).
+    /// `None` lets the process run until it halts, faults, or blocks on I/O.
+    pub fn execute_process(&mut self, in_pcb: Option<Arc<Mutex<ProcessControlBlock>>>, out_pcb: Option<Arc<Mutex<ProcessControlBlock>>>, quantum: Option<usize>) {
         if in_pcb.is_none() && out_pcb.is_none() {
             panic!("At least one of in_pcb or out_pcb must be Some.");
         }
-        
+
         let mut resources = self.resources.lock().unwrap();
-        
+
         if let Some(out_pcb) = out_pcb {
             let mut out_pcb = out_pcb.lock().unwrap();
 
             out_pcb.program_counter = resources.program_counter;
             out_pcb.registers.copy_from_slice(&resources.registers);
+            out_pcb.flags = resources.flags;
             out_pcb.end_record_burst_time();
+
+            if in_pcb.is_none() {
+                // Nothing is being dispatched in this same call, so the
+                // core goes idle until the next `execute_process` call --
+                // clear `current_process_id` so a DMA completion racing
+                // against this write-back (see the `IoFetch` handler)
+                // knows the PCB it just updated is the only copy left,
+                // rather than writing into `resources.registers` on
+                // behalf of a process that isn't live on the core anymore.
+                resources.current_process_id = 0;
+            }
         }
 
         if let Some(in_pcb) = in_pcb {
@@ -85,16 +171,55 @@ impl Cpu {
                 let memory = resources.memory.read().unwrap();
                 memory.read_block_from(in_pcb.get_mem_start_address(), in_pcb.get_mem_in_start_address())
             };
+            // Pre-decode the whole instruction buffer once up front instead
+            // of re-decoding the same raw word every time `cycle` fetches
+            // it -- `store`'s self-modifying guard clears a slot back to
+            // `None` if the process ever overwrites it, so `cycle` falls
+            // back to decoding that word on demand.
+            resources.decoded_cache = resources.cache.iter().map(|&word| Some(Cpu::decode(word))).collect();
             resources.program_counter = in_pcb.program_counter;
             resources.mem_start_address = in_pcb.get_mem_start_address();
+            resources.current_process_id = in_pcb.get_id();
             resources.registers.copy_from_slice(&in_pcb.registers);
+            resources.flags = in_pcb.flags;
+            resources.quantum = quantum;
+            resources.instructions_executed_in_quantum = 0;
 
             let (lock, condvar) = &*resources.proc_should_interrupt_condvar;
             let mut should_interrupt = lock.lock().unwrap();
 
             *should_interrupt = false;
             condvar.notify_all();
-        }        
+        }
+    }
+
+    /// Runs `pcb` for at most `max_instructions`, writes its PC, registers,
+    /// and flags back into the PCB (the same write-back `execute_process`
+    /// does for an `out_pcb`), and reports why it stopped. A synchronous,
+    /// single-process counterpart to `execute_process`/`await_process_interrupt`
+    /// for a caller that wants to step one process forward by hand --
+    /// loading jobs 1-4 and calling this round-robin across them is enough
+    /// to observe interleaved execution -- without standing up a full
+    /// `ShortTermScheduler` dispatch loop.
+    pub fn run_quantum(&mut self, pcb: Arc<Mutex<ProcessControlBlock>>, max_instructions: usize) -> RunOutcome {
+        self.execute_process(Some(pcb.clone()), None, Some(max_instructions));
+
+        let state = self.await_process_interrupt();
+        let trap_cause = self.take_trap_cause();
+        let instructions_executed = self.take_instructions_executed_in_quantum();
+
+        self.execute_process(None, Some(pcb.clone()), None);
+        pcb.lock().unwrap().record_instructions_executed(instructions_executed);
+
+        match state {
+            ProcessState::Terminated => RunOutcome::Halted,
+            ProcessState::Waiting => RunOutcome::Waiting,
+            ProcessState::SystemCall => RunOutcome::SystemCall,
+            ProcessState::Preempted => RunOutcome::Preempted,
+            ProcessState::Faulted => RunOutcome::Faulted(trap_cause.expect("Faulted interrupt without a recorded trap cause")),
+            ProcessState::Ready | ProcessState::Running | ProcessState::Breakpoint =>
+                panic!("run_quantum doesn't support breakpoints or a pre-set Ready/Running state"),
+        }
     }
 
     pub fn await_process_interrupt(&self) -> ProcessState {
@@ -110,10 +235,225 @@ impl Cpu {
             should_interrupt = condvar.wait(should_interrupt).unwrap();
         }
 
+        // `cycle` always locks `resources` before the condvar's own mutex
+        // (see `already_interrupted`), e.g. to check whether a watchpoint
+        // or quantum expiry should still raise its own interrupt after this
+        // one already fired -- holding `should_interrupt` into the lock
+        // below would acquire the two in the opposite order and deadlock
+        // against it.
+        drop(should_interrupt);
+
         self.resources.lock().unwrap().proc_interrupt_type
     }
 
-    fn cycle(resources: &Arc<Mutex<CpuResources>>) {
+    /// Reads back what tripped a `ProcessState::Faulted` interrupt, if the
+    /// process most recently dispatched on this CPU trapped rather than
+    /// finishing normally. Takes the cause so a later, unrelated trap can't
+    /// be misread as belonging to a process that never faulted.
+    pub fn take_trap_cause(&self) -> Option<TrapCause> {
+        self.resources.lock().unwrap().trap_cause.take()
+    }
+
+    /// Reads back the pending `TRAP`/`SWI` request once
+    /// `await_process_interrupt` reports `ProcessState::SystemCall`. Takes
+    /// the request so a later, unrelated syscall can't be misread as this
+    /// one's.
+    pub fn take_pending_syscall(&self) -> Option<PendingSyscall> {
+        self.resources.lock().unwrap().pending_syscall.take()
+    }
+
+    /// Reads back how many instructions the process most recently dispatched
+    /// on this CPU ran, whether it used its whole `quantum` (see
+    /// `ProcessState::Preempted`) or gave the CPU back early by halting,
+    /// faulting, or blocking on I/O. Takes the count so the next process's
+    /// instructions can't be folded into a prior one's total.
+    pub fn take_instructions_executed_in_quantum(&self) -> usize {
+        let mut resources = self.resources.lock().unwrap();
+        std::mem::take(&mut resources.instructions_executed_in_quantum)
+    }
+
+    /// Registers an address (in bytes, consistent with branch targets) as
+    /// a breakpoint: `cycle` pauses with `ProcessState::Breakpoint` just
+    /// before executing whatever instruction next occupies it. Not wired
+    /// into `Driver` -- meant to be driven by hand (or a future front-end)
+    /// the way `execute_process_debug`'s own tests use it.
+    #[allow(dead_code)]
+    pub fn set_breakpoint(&self, address: usize) {
+        self.resources.lock().unwrap().breakpoints.insert(address / 4);
+    }
+
+    #[allow(dead_code)]
+    pub fn clear_breakpoint(&self, address: usize) {
+        self.resources.lock().unwrap().breakpoints.remove(&(address / 4));
+    }
+
+    /// Registers an absolute `Memory` word index (unlike `set_breakpoint`'s
+    /// per-process byte address -- a watchpoint is about where the data
+    /// lives, not where the code that touches it lives) as a watchpoint:
+    /// `cycle` pauses with `ProcessState::Breakpoint` right after any
+    /// instruction that reads or writes it via `Cpu::fetch`/`Cpu::store`
+    /// (`LW`, `ST`; not the DMA-backed `RD`/`WR`). Not wired into `Driver`
+    /// -- see `Cpu::set_breakpoint`.
+    #[allow(dead_code)]
+    pub fn set_watchpoint(&self, address: usize) {
+        self.resources.lock().unwrap().watchpoints.insert(address);
+    }
+
+    #[allow(dead_code)]
+    pub fn clear_watchpoint(&self, address: usize) {
+        self.resources.lock().unwrap().watchpoints.remove(&address);
+    }
+
+    /// Installs `handler` for the given `(instr_type, opcode)` pair,
+    /// overwriting whatever `default_opcode_table` put there (including an
+    /// unmapped slot's illegal-instruction trap). Lets new pseudo-instructions
+    /// -- custom I/O devices, syscalls, extended arithmetic -- or an entirely
+    /// different instruction set be added without touching `execute`'s core
+    /// dispatch. Not wired into `Driver` on its own -- see
+    /// `Driver::register_opcode`, which forwards to this on every core but
+    /// likewise has no caller yet.
+    #[allow(dead_code)]
+    pub fn register_opcode(&self, instr_type: u8, opcode: u8, handler: OpcodeHandler) {
+        let mut resources = self.resources.lock().unwrap();
+        resources.opcode_table[Cpu::opcode_table_index(instr_type, opcode)] = handler;
+    }
+
+    /// Resumes a process paused at a `ProcessState::Breakpoint` for exactly
+    /// one more instruction, bypassing whatever breakpoint it's currently
+    /// sitting on so stepping past it doesn't just retrigger the same stop,
+    /// then pauses again with `ProcessState::Breakpoint` before the next
+    /// fetch. Call `await_process_interrupt` afterward the same way as
+    /// after `execute_process`. Not wired into `Driver` -- see `Cpu::set_breakpoint`.
+    #[allow(dead_code)]
+    pub fn step(&mut self) {
+        let mut resources = self.resources.lock().unwrap();
+        resources.single_step = true;
+
+        let (lock, condvar) = &*resources.proc_should_interrupt_condvar;
+        let mut should_interrupt = lock.lock().unwrap();
+
+        *should_interrupt = false;
+        condvar.notify_all();
+    }
+
+    /// Dispatches `pcb` the same way `execute_process` does, then waits
+    /// for the first breakpoint, watchpoint, or ordinary stop and reports
+    /// it as a `DebugStop` -- the entry point a debugging front-end uses
+    /// instead of `execute_process`/`await_process_interrupt` directly.
+    /// Not wired into `Driver` -- see `Cpu::set_watchpoint`.
+    #[allow(dead_code)]
+    pub fn execute_process_debug(&mut self, pcb: Arc<Mutex<ProcessControlBlock>>) -> DebugStop {
+        self.execute_process(Some(pcb), None, None);
+        self.await_debug_stop()
+    }
+
+    /// Resumes a process paused at a breakpoint or watchpoint, bypassing
+    /// whichever one it's currently sitting on just once (the same
+    /// one-time bypass `step` uses) but -- unlike `step` -- running
+    /// freely afterward instead of pausing again after exactly one
+    /// instruction. Not wired into `Driver` -- see `Cpu::set_watchpoint`.
+    #[allow(dead_code)]
+    pub fn continue_execution(&mut self) -> DebugStop {
+        let proc_should_interrupt_condvar = {
+            let mut resources = self.resources.lock().unwrap();
+            resources.bypass_breakpoint_once = true;
+            resources.proc_should_interrupt_condvar.clone()
+        };
+
+        let (lock, condvar) = &*proc_should_interrupt_condvar;
+        let mut should_interrupt = lock.lock().unwrap();
+
+        *should_interrupt = false;
+        condvar.notify_all();
+
+        drop(should_interrupt);
+        self.await_debug_stop()
+    }
+
+    /// Waits for `cycle` to pause (the same wait `await_process_interrupt`
+    /// does) and packages the result for a debugging front-end: the
+    /// register file, the PC it stopped at, and why, folding in whatever
+    /// trap cause or breakpoint/watchpoint cause `cycle` left behind. Not
+    /// wired into `Driver` -- see `Cpu::set_watchpoint`.
+    #[allow(dead_code)]
+    fn await_debug_stop(&self) -> DebugStop {
+        let state = self.await_process_interrupt();
+        let trap_cause = self.take_trap_cause();
+        let breakpoint_cause = self.resources.lock().unwrap().breakpoint_cause.take();
+
+        let (pc, registers) = {
+            let resources = self.resources.lock().unwrap();
+            (resources.program_counter, resources.registers)
+        };
+
+        let reason = match state {
+            ProcessState::Terminated => StopReason::Halted,
+            ProcessState::Waiting => StopReason::Waiting,
+            ProcessState::SystemCall => StopReason::SystemCall,
+            ProcessState::Preempted => StopReason::Preempted,
+            ProcessState::Faulted => StopReason::Faulted(trap_cause.expect("Faulted interrupt without a recorded trap cause")),
+            ProcessState::Breakpoint => match breakpoint_cause.expect("Breakpoint interrupt without a recorded cause") {
+                BreakpointCause::Breakpoint => StopReason::Breakpoint,
+                BreakpointCause::Watchpoint(address) => StopReason::Watchpoint(address),
+                BreakpointCause::Step => StopReason::Stepped,
+            },
+            ProcessState::Ready | ProcessState::Running =>
+                panic!("await_debug_stop doesn't support a pre-set Ready/Running state"),
+        };
+
+        DebugStop { pc, reason, registers }
+    }
+
+    /// Snapshots enough CPU state for a debugging front-end to print at
+    /// each stop: the program counter, the full register file, the
+    /// process's memory window, and the next instruction `cycle` is about
+    /// to fetch (decoded via the same `decode` the CPU itself uses), or
+    /// `None` if the program counter has run off the end of the process's
+    /// instruction buffer. Not wired into `Driver` -- see `Cpu::set_breakpoint`.
+    #[allow(dead_code)]
+    pub fn dump_state(&self) -> CpuStateSnapshot {
+        let resources = self.resources.lock().unwrap();
+
+        let next_instruction = resources.cache
+            .get(resources.program_counter)
+            .map(|&instruction| Cpu::decode(instruction));
+
+        CpuStateSnapshot {
+            program_counter: resources.program_counter,
+            registers: resources.registers,
+            mem_start_address: resources.mem_start_address,
+            next_instruction,
+        }
+    }
+
+    /// Resumes the same process that issued the `TRAP`, without reloading
+    /// its cache from memory the way `execute_process` would for a newly
+    /// dispatched process -- the process never left the CPU, so its
+    /// cached instructions and program counter are already exactly where
+    /// it left off. For a syscall that hands back a return value, use
+    /// `resume_after_syscall` instead.
+    pub fn resume(&mut self) {
+        let resources = self.resources.lock().unwrap();
+
+        let (lock, condvar) = &*resources.proc_should_interrupt_condvar;
+        let mut should_interrupt = lock.lock().unwrap();
+
+        *should_interrupt = false;
+        condvar.notify_all();
+    }
+
+    /// Writes a syscall's return value into `return_reg_num`, then resumes
+    /// exactly like `resume`.
+    pub fn resume_after_syscall(&mut self, return_reg_num: usize, return_value: u32) {
+        {
+            let mut resources = self.resources.lock().unwrap();
+            resources.registers[return_reg_num] = return_value;
+        }
+
+        self.resume();
+    }
+
+    fn cycle(resources: &Arc<Mutex<CpuResources>>, cycle_should_terminate: &Arc<AtomicBool>) {
         // Sleep until a process is ready to be executed.
         let proc_should_interrupt_convar = {
             let resources = resources.lock().unwrap();
@@ -124,20 +464,103 @@ impl Cpu {
             let (lock, condvar) = &*proc_should_interrupt_convar;
             let mut should_interrupt = lock.lock().unwrap();
 
-            while *should_interrupt {
+            while *should_interrupt && !cycle_should_terminate.load(Ordering::Relaxed) {
                 should_interrupt = condvar.wait(should_interrupt).unwrap();
             }
         }
 
+        // `Cpu::drop` notifies this condvar to wake a parked cycle thread
+        // just so it can observe `cycle_should_terminate` and exit, not
+        // because a process actually became ready -- don't fetch/execute
+        // in that case.
+        if cycle_should_terminate.load(Ordering::Relaxed) {
+            return;
+        }
+
         // Execute instruction.
         let mut resources = resources.lock().unwrap();
 
-        let current_instruction = resources.cache[resources.program_counter]; // Fetch.
+        if resources.program_counter >= resources.cache.len() {
+            Cpu::trap(&mut resources, TrapCause::AddressOutOfBounds);
+            return;
+        }
+
+        let fetch_pc = resources.program_counter;
+        let current_instruction = resources.cache[fetch_pc]; // Fetch.
+
+        // Pause before running the instruction that's about to execute if
+        // it's a breakpoint, unless this is the one instruction a debugger
+        // asked to step past it via `step()` or `continue_execution()`.
+        if !resources.single_step && !resources.bypass_breakpoint_once && resources.breakpoints.contains(&fetch_pc) {
+            resources.breakpoint_cause = Some(BreakpointCause::Breakpoint);
+            Cpu::signal_interrupt(&mut resources, ProcessState::Breakpoint);
+            return;
+        }
+
+        let was_single_stepping = resources.single_step;
+        resources.single_step = false;
+        resources.bypass_breakpoint_once = false;
+
         resources.program_counter += 1;
 
-        let decoded_instruction = Cpu::decode(current_instruction);
+        let decoded_instruction = match resources.decoded_cache.get(fetch_pc).copied().flatten() {
+            Some(decoded) => decoded,
+            None => Cpu::decode(current_instruction), // Outside the cached range, or invalidated by a store.
+        };
+        let registers_before = resources.registers;
 
+        resources.last_memory_access = None;
         Cpu::execute(&mut resources, &decoded_instruction);
+
+        if resources.trace {
+            Cpu::log_trace(&resources, fetch_pc, current_instruction, &registers_before);
+        }
+
+        // Pause immediately after an instruction that read or wrote a
+        // watched address, the same way a breakpoint pauses before one --
+        // except this fires after the instruction that touched the
+        // address runs, since there's no way to know it's a hit before
+        // `execute` resolves the address (e.g. a pointer register). Skipped
+        // if `execute` already raised a terminal interrupt of its own (e.g.
+        // `Cpu::fault`/`Cpu::trap` on an out-of-bounds access), so a real
+        // fault can't be silently overwritten with a benign watchpoint stop.
+        if !Cpu::already_interrupted(&resources) {
+            if let Some(address) = resources.last_memory_access {
+                if resources.watchpoints.contains(&address) {
+                    resources.breakpoint_cause = Some(BreakpointCause::Watchpoint(address));
+                    Cpu::signal_interrupt(&mut resources, ProcessState::Breakpoint);
+                    return;
+                }
+            }
+        }
+
+        // Counted with or without a quantum, so
+        // `take_instructions_executed_in_quantum` reports every dispatch.
+        resources.instructions_executed_in_quantum += 1;
+
+        // Preempt the process if it has run for its whole time slice and
+        // hasn't already interrupted itself (e.g. via HLT or a fault).
+        if let Some(quantum) = resources.quantum {
+            if resources.instructions_executed_in_quantum >= quantum && !Cpu::already_interrupted(&resources) {
+                Cpu::signal_interrupt(&mut resources, ProcessState::Preempted);
+            }
+        }
+
+        // A single-stepped instruction pauses again immediately rather
+        // than running freely until the next breakpoint or quantum.
+        if was_single_stepping && !Cpu::already_interrupted(&resources) {
+            resources.breakpoint_cause = Some(BreakpointCause::Step);
+            Cpu::signal_interrupt(&mut resources, ProcessState::Breakpoint);
+        }
+    }
+
+    /// Whether `cycle`'s `execute` call already raised a terminal interrupt
+    /// for this instruction (a fault, a halt, a syscall, or an I/O wait) --
+    /// used to stop the watchpoint/quantum/single-step checks below it from
+    /// overwriting that interrupt with one of their own.
+    fn already_interrupted(resources: &CpuResources) -> bool {
+        let (lock, _) = &*resources.proc_should_interrupt_condvar;
+        *lock.lock().unwrap()
     }
 
     fn decode(instruction: u32) -> DecodedInstruction {
@@ -179,170 +602,405 @@ impl Cpu {
         (instruction << start_index) >> (32 - length)
     }
 
+    /// Prints one trace line for an instruction `cycle` just executed: the
+    /// PC it was fetched from (in bytes, matching branch targets), its
+    /// disassembly (see `disassemble::disassemble`), and the register file
+    /// before and after, so a failing job (e.g. `test_execute_job2`) can be
+    /// debugged from an execution trace instead of only post-mortem memory.
+    fn log_trace(resources: &CpuResources, fetch_pc: usize, raw_instruction: u32, registers_before: &[u32; 16]) {
+        eprintln!(
+            "{:#06X}: {:<28} before={:?} after={:?}",
+            fetch_pc * 4,
+            disassemble::disassemble(raw_instruction).to_string(),
+            registers_before,
+            resources.registers,
+        );
+    }
+
+    /// Looks the instruction's `(instr_type, opcode)` up in
+    /// `CpuResources::opcode_table` and calls whatever handler is
+    /// registered there -- see `register_opcode` for how entries not set
+    /// up by `default_opcode_table` get there.
     fn execute(resources: &mut CpuResources, instruction: &DecodedInstruction) {
-        // No-op.
-        if instruction.opcode == 0x13 {
-            return;
-        }
+        let handler = resources.opcode_table[Cpu::opcode_table_index(instruction.instr_type, instruction.opcode)];
+        handler(resources, instruction);
+    }
 
-        match instruction.instr_type {
-            0b00 => Cpu::execute_arithmetic(resources, instruction),
-            0b01 => Cpu::execute_cond_branch_immediate(resources, instruction),
-            0b10 => Cpu::execute_uncond_jump(resources, instruction),
-            0b11 => Cpu::execute_io(resources, instruction),
-            _ => panic!("Execute error, invalid instruction type"),
+    /// Flattens a `(instr_type, opcode)` pair into `CpuResources::opcode_table`'s
+    /// index space: 2-bit `instr_type`, 6-bit `opcode`.
+    fn opcode_table_index(instr_type: u8, opcode: u8) -> usize {
+        (instr_type as usize) * 64 + opcode as usize
+    }
+
+    /// Builds the opcode table every `CpuResources` starts with, mapping
+    /// each of the ISA's current opcodes to its handler and leaving every
+    /// other slot pointing at `execute_illegal_instruction`.
+    fn default_opcode_table() -> [OpcodeHandler; OPCODE_TABLE_LEN] {
+        let mut table: [OpcodeHandler; OPCODE_TABLE_LEN] = [Cpu::execute_illegal_instruction; OPCODE_TABLE_LEN];
+
+        for instr_type in 0..4 {
+            table[Cpu::opcode_table_index(instr_type, 0x13)] = Cpu::execute_noop;
         }
+
+        table[Cpu::opcode_table_index(0b00, 0x4)] = Cpu::execute_mov;
+        table[Cpu::opcode_table_index(0b00, 0x5)] = Cpu::execute_add;
+        table[Cpu::opcode_table_index(0b00, 0x6)] = Cpu::execute_sub;
+        table[Cpu::opcode_table_index(0b00, 0x7)] = Cpu::execute_mul;
+        table[Cpu::opcode_table_index(0b00, 0x8)] = Cpu::execute_div;
+        table[Cpu::opcode_table_index(0b00, 0x9)] = Cpu::execute_and;
+        table[Cpu::opcode_table_index(0b00, 0xA)] = Cpu::execute_or;
+        table[Cpu::opcode_table_index(0b00, 0x10)] = Cpu::execute_slt;
+
+        table[Cpu::opcode_table_index(0b01, 0x2)] = Cpu::execute_st;
+        table[Cpu::opcode_table_index(0b01, 0x3)] = Cpu::execute_lw;
+        table[Cpu::opcode_table_index(0b01, 0xB)] = Cpu::execute_movi;
+        table[Cpu::opcode_table_index(0b01, 0xC)] = Cpu::execute_addi;
+        table[Cpu::opcode_table_index(0b01, 0xD)] = Cpu::execute_muli;
+        table[Cpu::opcode_table_index(0b01, 0xE)] = Cpu::execute_divi;
+        table[Cpu::opcode_table_index(0b01, 0xF)] = Cpu::execute_ldi;
+        table[Cpu::opcode_table_index(0b01, 0x11)] = Cpu::execute_slti;
+        table[Cpu::opcode_table_index(0b01, 0x15)] = Cpu::execute_beq;
+        table[Cpu::opcode_table_index(0b01, 0x16)] = Cpu::execute_bne;
+        table[Cpu::opcode_table_index(0b01, 0x17)] = Cpu::execute_bez;
+        table[Cpu::opcode_table_index(0b01, 0x18)] = Cpu::execute_bnz;
+        table[Cpu::opcode_table_index(0b01, 0x19)] = Cpu::execute_bgz;
+        table[Cpu::opcode_table_index(0b01, 0x1A)] = Cpu::execute_blz;
+
+        table[Cpu::opcode_table_index(0b10, 0x12)] = Cpu::execute_hlt;
+        table[Cpu::opcode_table_index(0b10, 0x14)] = Cpu::execute_jmp;
+
+        table[Cpu::opcode_table_index(0b11, 0x0)] = Cpu::execute_rd;
+        table[Cpu::opcode_table_index(0b11, 0x1)] = Cpu::execute_wr;
+        table[Cpu::opcode_table_index(0b11, 0x2)] = Cpu::execute_trap;
+
+        table
     }
 
-    fn execute_arithmetic(resources: &mut CpuResources, instruction: &DecodedInstruction) {
-        match instruction.opcode {
-            0x4 => /* MOV */ Cpu::set_reg(resources, instruction.reg_1_num, Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x5 => /* ADD */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) + Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x6 => /* SUB */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) - Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x7 => /* MUL */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) * Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x8 => /* DIV */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) / Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x9 => /* AND */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) & Cpu::get_reg(resources, instruction.reg_2_num)),
-            0xA => /* OR */ Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) | Cpu::get_reg(resources, instruction.reg_2_num)),
-            0x10 => /* SLT */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) < Cpu::get_reg(resources, instruction.reg_2_num) {
-                    Cpu::set_reg(resources, instruction.reg_3_num, 1);
-                } else {
-                    Cpu::set_reg(resources, instruction.reg_3_num, 0);
-                }
-            },
-            _ => panic!("Execute error, invalid opcode for arithmetic instruction"),
-        };
+    fn execute_illegal_instruction(resources: &mut CpuResources, _instruction: &DecodedInstruction) {
+        Cpu::trap(resources, TrapCause::IllegalInstruction);
     }
 
-    fn execute_cond_branch_immediate(resources: &mut CpuResources, instruction: &DecodedInstruction) {
-        match instruction.opcode {
-            0x2 =>  /* ST */ {
-                // Register 0 is the accumulator, which will never be used as a pointer.
-                if instruction.reg_2_num == 0 {
-                    let value = Cpu::get_reg(resources, instruction.reg_1_num);
-                    Cpu::store(resources, instruction.address, value);
-                } else { // Use contents of reg2 as address.
-                    let address = Cpu::get_reg(resources, instruction.reg_2_num) as usize;
-                    let value = Cpu::get_reg(resources, instruction.reg_1_num);
-                    Cpu::store(resources, address, value);
-                }
-            },
-            0x3 =>  /* LW */ {
-                // Register 0 is the accumulator, which will never be used as a pointer.
-                if instruction.reg_1_num == 0 {
-                    let value = Cpu::fetch(resources, instruction.address);
-                    Cpu::set_reg(resources, instruction.reg_2_num, value);
-                } else { // Use contents of reg1 as address.
-                    let address = Cpu::get_reg(resources, instruction.reg_1_num) as usize;
-                    let value = Cpu::fetch(resources, address);
-                    Cpu::set_reg(resources, instruction.reg_2_num, value);
-                }
-            },
-            0xB =>  /* MOVI */ Cpu::set_reg(resources, instruction.reg_2_num, instruction.address as u32),
-            0xC =>  /* ADDI */ Cpu::set_reg(resources, instruction.reg_2_num, Cpu::get_reg(resources, instruction.reg_2_num) + instruction.address as u32),
-            0xD =>  /* MULI */ Cpu::set_reg(resources, instruction.reg_2_num, Cpu::get_reg(resources, instruction.reg_2_num) * instruction.address as u32),
-            0xE =>  /* DIVI */ Cpu::set_reg(resources, instruction.reg_2_num, Cpu::get_reg(resources, instruction.reg_2_num) / instruction.address as u32),
-            0xF =>  /* LDI  */ Cpu::set_reg(resources, instruction.reg_2_num, instruction.address as u32),
-            0x11 => /* SLTI */ {
-                if Cpu::get_reg(resources, instruction.reg_2_num) < instruction.address as u32 {
-                    Cpu::set_reg(resources, instruction.reg_1_num, 1);
-                } else {
-                    Cpu::set_reg(resources, instruction.reg_1_num, 0);
-                }
-            },
-            0x15 => /* BEQ */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) == Cpu::get_reg(resources, instruction.reg_2_num) {
-                    Cpu::branch(resources, instruction.address);
-                }
-            },
-            0x16 => /* BNE */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) != Cpu::get_reg(resources, instruction.reg_2_num) {
-                    Cpu::branch(resources, instruction.address);
-                }
-            },
-            0x17 => /* BEZ */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) == 0 {
-                    Cpu::branch(resources, instruction.address);
-                }
-            },
-            0x18 => /* BNZ */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) != 0 {
-                    Cpu::branch(resources, instruction.address);
-                }
-            },
-            0x19 => /* BGZ */ {
-                if Cpu::get_reg(resources, instruction.reg_1_num) > 0 {
-                    Cpu::branch(resources, instruction.address);
-                }
-            },
-            0x1A => /* BLZ */ {
-                #[allow(unused_comparisons)]
-                if Cpu::get_reg(resources, instruction.reg_1_num) < 0 {
-                    Cpu::branch(resources, instruction.address);
-                }
+    fn execute_noop(_resources: &mut CpuResources, _instruction: &DecodedInstruction) {}
+
+    fn execute_mov(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_1_num, Cpu::get_reg(resources, instruction.reg_2_num));
+    }
+
+    fn execute_add(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let a = Cpu::get_reg(resources, instruction.reg_1_num);
+        let b = Cpu::get_reg(resources, instruction.reg_2_num);
+        let (result, carry) = a.overflowing_add(b);
+        let (_, overflow) = (a as i32).overflowing_add(b as i32);
+
+        Cpu::set_reg(resources, instruction.reg_3_num, result);
+        resources.flags = Cpu::compute_flags(result, carry, overflow);
+    }
+
+    fn execute_sub(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let a = Cpu::get_reg(resources, instruction.reg_1_num);
+        let b = Cpu::get_reg(resources, instruction.reg_2_num);
+        let (result, carry) = a.overflowing_sub(b);
+        let (_, overflow) = (a as i32).overflowing_sub(b as i32);
+
+        Cpu::set_reg(resources, instruction.reg_3_num, result);
+        resources.flags = Cpu::compute_flags(result, carry, overflow);
+    }
+
+    fn execute_mul(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let a = Cpu::get_reg(resources, instruction.reg_1_num);
+        let b = Cpu::get_reg(resources, instruction.reg_2_num);
+        let (result, carry) = a.overflowing_mul(b);
+        let (_, overflow) = (a as i32).overflowing_mul(b as i32);
+
+        Cpu::set_reg(resources, instruction.reg_3_num, result);
+        resources.flags = Cpu::compute_flags(result, carry, overflow);
+    }
+
+    fn execute_div(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let divisor = Cpu::get_reg(resources, instruction.reg_2_num);
+
+        match Cpu::get_reg(resources, instruction.reg_1_num).checked_div(divisor) {
+            Some(result) => {
+                Cpu::set_reg(resources, instruction.reg_3_num, result);
+                resources.flags = Cpu::compute_flags(result, false, false);
             },
-            _ => panic!("Execute error, invalid opcode for conditional branch or immediate instruction"),
+            None => Cpu::trap(resources, TrapCause::DivideByZero),
+        }
+    }
+
+    fn execute_and(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) & Cpu::get_reg(resources, instruction.reg_2_num));
+    }
+
+    fn execute_or(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_3_num, Cpu::get_reg(resources, instruction.reg_1_num) | Cpu::get_reg(resources, instruction.reg_2_num));
+    }
+
+    fn execute_slt(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        if Cpu::get_reg(resources, instruction.reg_1_num) < Cpu::get_reg(resources, instruction.reg_2_num) {
+            Cpu::set_reg(resources, instruction.reg_3_num, 1);
+        } else {
+            Cpu::set_reg(resources, instruction.reg_3_num, 0);
+        }
+    }
+
+    fn execute_st(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        // Register 0 is the accumulator, which will never be used as a pointer.
+        let address = if instruction.reg_2_num == 0 {
+            instruction.address
+        } else { // Use contents of reg2 as address.
+            Cpu::get_reg(resources, instruction.reg_2_num) as usize
         };
+        let value = Cpu::get_reg(resources, instruction.reg_1_num);
+
+        if let Err(fault) = Cpu::store(resources, address, value) {
+            Cpu::fault(resources, fault);
+        }
     }
 
-    fn execute_uncond_jump(resources: &mut CpuResources, instruction: &DecodedInstruction) {
-        match instruction.opcode {
-            0x12 => /* HLT */ {
-                Cpu::signal_interrupt(resources, ProcessState::Terminated);
-            },
-            0x14 => /* JMP */ Cpu::branch(resources, instruction.address),
-            _ => panic!("Execute error, invalid opcode for unconditional jump instruction"),
+    fn execute_lw(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        // Register 0 is the accumulator, which will never be used as a pointer.
+        let address = if instruction.reg_1_num == 0 {
+            instruction.address
+        } else { // Use contents of reg1 as address.
+            Cpu::get_reg(resources, instruction.reg_1_num) as usize
         };
+
+        match Cpu::fetch(resources, address) {
+            Ok(value) => Cpu::set_reg(resources, instruction.reg_2_num, value),
+            Err(fault) => Cpu::fault(resources, fault),
+        }
     }
 
-    fn execute_io(resources: &mut CpuResources, instruction: &DecodedInstruction) {
-        match instruction.opcode {
-            0x0 => /* RD */ {
-                let (response_sender, response_receiver) = mpsc::channel();
-
-                // Register 0 is the accumulator, which will never be used as a pointer.
-                if instruction.reg_2_num == 0 {
-                    let address = instruction.address / 4 + resources.mem_start_address;
-                    resources.dma_sender.send(DmaCommand::Fetch { address, response_sender }).unwrap();
-                    let value = response_receiver.recv().unwrap();
-                    Cpu::set_reg(resources, instruction.reg_1_num, value);
-                } else { // Use contents of reg2 as address.
-                    let address = Cpu::get_reg(resources, instruction.reg_2_num) as usize / 4 + resources.mem_start_address;
-                    resources.dma_sender.send(DmaCommand::Fetch { address, response_sender }).unwrap();
-                    let value = response_receiver.recv().unwrap();
-                    Cpu::set_reg(resources, instruction.reg_1_num, value);
-                }
+    fn execute_movi(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_2_num, instruction.address as u32);
+    }
+
+    fn execute_addi(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let a = Cpu::get_reg(resources, instruction.reg_2_num);
+        let b = instruction.address as u32;
+        let (result, carry) = a.overflowing_add(b);
+        let (_, overflow) = (a as i32).overflowing_add(b as i32);
+
+        Cpu::set_reg(resources, instruction.reg_2_num, result);
+        resources.flags = Cpu::compute_flags(result, carry, overflow);
+    }
+
+    fn execute_muli(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let a = Cpu::get_reg(resources, instruction.reg_2_num);
+        let b = instruction.address as u32;
+        let (result, carry) = a.overflowing_mul(b);
+        let (_, overflow) = (a as i32).overflowing_mul(b as i32);
+
+        Cpu::set_reg(resources, instruction.reg_2_num, result);
+        resources.flags = Cpu::compute_flags(result, carry, overflow);
+    }
+
+    fn execute_divi(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let divisor = instruction.address as u32;
+
+        match Cpu::get_reg(resources, instruction.reg_2_num).checked_div(divisor) {
+            Some(result) => {
+                Cpu::set_reg(resources, instruction.reg_2_num, result);
+                resources.flags = Cpu::compute_flags(result, false, false);
             },
-            0x1 => /* WR */ {
-                let (response_sender, response_receiver) = mpsc::channel();
-
-                // Register 0 is the accumulator, which will never be used as a pointer.
-                if instruction.reg_2_num == 0 {
-                    let address = instruction.address / 4 + resources.mem_start_address;
-                    let value = Cpu::get_reg(resources, instruction.reg_1_num);
-                    resources.dma_sender.send(DmaCommand::Store { address, value, response_sender }).unwrap();
-                    response_receiver.recv().unwrap();
-                } else { // Use contents of reg2 as address.
-                    let address = Cpu::get_reg(resources, instruction.reg_2_num) as usize / 4 + resources.mem_start_address;
-                    let value = Cpu::get_reg(resources, instruction.reg_1_num);
-                    resources.dma_sender.send(DmaCommand::Store { address, value, response_sender }).unwrap();
-                    response_receiver.recv().unwrap();
-                }
+            None => Cpu::trap(resources, TrapCause::DivideByZero),
+        }
+    }
+
+    fn execute_ldi(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_2_num, instruction.address as u32);
+    }
+
+    fn execute_slti(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        if Cpu::get_reg(resources, instruction.reg_2_num) < instruction.address as u32 {
+            Cpu::set_reg(resources, instruction.reg_1_num, 1);
+        } else {
+            Cpu::set_reg(resources, instruction.reg_1_num, 0);
+        }
+    }
+
+    fn execute_beq(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        if Cpu::get_reg(resources, instruction.reg_1_num) == Cpu::get_reg(resources, instruction.reg_2_num) {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_bne(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        if Cpu::get_reg(resources, instruction.reg_1_num) != Cpu::get_reg(resources, instruction.reg_2_num) {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_bez(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let flags = Cpu::compute_flags(Cpu::get_reg(resources, instruction.reg_1_num), false, false);
+        resources.flags = flags;
+
+        if flags.zero {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_bnz(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let flags = Cpu::compute_flags(Cpu::get_reg(resources, instruction.reg_1_num), false, false);
+        resources.flags = flags;
+
+        if !flags.zero {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_bgz(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let flags = Cpu::compute_flags(Cpu::get_reg(resources, instruction.reg_1_num), false, false);
+        resources.flags = flags;
+
+        if !flags.zero && !flags.negative {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_blz(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        let flags = Cpu::compute_flags(Cpu::get_reg(resources, instruction.reg_1_num), false, false);
+        resources.flags = flags;
+
+        if flags.negative {
+            Cpu::branch(resources, instruction.address);
+        }
+    }
+
+    fn execute_hlt(resources: &mut CpuResources, _instruction: &DecodedInstruction) {
+        Cpu::signal_interrupt(resources, ProcessState::Terminated);
+    }
+
+    fn execute_jmp(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::branch(resources, instruction.address);
+    }
+
+    /// Hands an `RD` request off to the DMA channel and signals `Waiting`
+    /// so the scheduler can park this process and run another one while
+    /// the DMA thread services the request asynchronously -- once it
+    /// completes, the DMA thread writes the result into this CPU's live
+    /// registers if the process is still the one loaded here, and into the
+    /// process's PCB otherwise, since by then the CPU may already be
+    /// running a different process.
+    fn execute_rd(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        // Register 0 is the accumulator, which will never be used as a pointer.
+        let address = if instruction.reg_2_num == 0 {
+            instruction.address / 4 + resources.mem_start_address
+        } else { // Use contents of reg2 as address.
+            Cpu::get_reg(resources, instruction.reg_2_num) as usize / 4 + resources.mem_start_address
+        };
+
+        // The DMA thread only has the raw, unchecked memory API, so the
+        // bounds check has to happen here -- before the address is handed
+        // off -- or a process could read out of its own region, and an
+        // address past `MEMORY_SIZE` would panic the DMA thread instead of
+        // faulting the offending process.
+        let bounds_check = {
+            let memory = resources.memory.read().unwrap();
+            memory.validate_bounds(resources.current_process_id, address)
+        };
+
+        match bounds_check {
+            Ok(()) => {
+                resources.dma_sender.send(DmaCommand::IoFetch {
+                    address,
+                    process_id: resources.current_process_id,
+                    reg_num: instruction.reg_1_num,
+                }).unwrap();
+
+                Cpu::signal_interrupt(resources, ProcessState::Waiting);
             },
-            _ => panic!("Execute error, invalid opcode for I/O jump instruction"),
+            Err(fault) => Cpu::fault(resources, fault),
+        }
+    }
+
+    /// Hands a `WR` request off to the DMA channel the same way `execute_rd` does.
+    fn execute_wr(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        // Register 0 is the accumulator, which will never be used as a pointer.
+        let address = if instruction.reg_2_num == 0 {
+            instruction.address / 4 + resources.mem_start_address
+        } else { // Use contents of reg2 as address.
+            Cpu::get_reg(resources, instruction.reg_2_num) as usize / 4 + resources.mem_start_address
+        };
+        let value = Cpu::get_reg(resources, instruction.reg_1_num);
+
+        let bounds_check = {
+            let memory = resources.memory.read().unwrap();
+            memory.validate_bounds(resources.current_process_id, address)
         };
+
+        match bounds_check {
+            Ok(()) => {
+                resources.dma_sender.send(DmaCommand::IoStore {
+                    address,
+                    value,
+                    process_id: resources.current_process_id,
+                }).unwrap();
+
+                Cpu::signal_interrupt(resources, ProcessState::Waiting);
+            },
+            Err(fault) => Cpu::fault(resources, fault),
+        }
+    }
+
+    /// Raises a software interrupt so a process can request a kernel
+    /// service (see `PendingSyscall`) without bringing down the CPU thread.
+    fn execute_trap(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        resources.pending_syscall = Some(PendingSyscall {
+            number: Cpu::get_reg(resources, instruction.reg_1_num),
+            registers: resources.registers,
+        });
+
+        Cpu::signal_interrupt(resources, ProcessState::SystemCall);
     }
 
-    fn fetch(resources: &CpuResources, address: usize) -> u32 {
+    fn fetch(resources: &mut CpuResources, address: usize) -> Result<u32, MemoryFault> {
+        let physical_address = Cpu::get_physical_address_for(resources, address / 4);
+        resources.last_memory_access = Some(physical_address);
+
         let memory = resources.memory.read().unwrap();
-        let address = Cpu::get_physical_address_for(resources, address / 4);
-        memory.read_from(address)
+        memory.read_for(resources.current_process_id, physical_address)
     }
 
-    fn store(resources: &mut CpuResources, address: usize, value: u32) {
-        let mut memory = resources.memory.write().unwrap();
-        let address = Cpu::get_physical_address_for(resources, address / 4);
-        memory.write_to(address, value);
+    fn store(resources: &mut CpuResources, address: usize, value: u32) -> Result<(), MemoryFault> {
+        let logical_address = address / 4;
+        let physical_address = Cpu::get_physical_address_for(resources, logical_address);
+        resources.last_memory_access = Some(physical_address);
+
+        {
+            let mut memory = resources.memory.write().unwrap();
+            memory.write_for(resources.current_process_id, physical_address, value)?;
+        }
+
+        // Self-modifying guard: a store into the instruction buffer
+        // invalidates whatever `execute_process` pre-decoded for that slot,
+        // so `cycle` decodes that slot again from the raw word cached at
+        // dispatch. The stored word itself only runs from the process's
+        // next dispatch, when `execute_process` reloads the cache from memory.
+        if let Some(slot) = resources.decoded_cache.get_mut(logical_address) {
+            *slot = None;
+        }
+
+        Ok(())
+    }
+
+    /// Traps a per-process memory protection violation by terminating the
+    /// offending process instead of panicking the CPU thread.
+    fn fault(resources: &mut CpuResources, fault: MemoryFault) {
+        eprintln!("Process {} faulted: out-of-bounds access at address {}", fault.process_id, fault.addr);
+        Cpu::signal_interrupt(resources, ProcessState::Terminated);
+    }
+
+    /// Traps a CPU-level fault -- an illegal instruction type, an
+    /// unrecognized opcode, a divide by zero, or a fetch past the end of
+    /// the process's instruction buffer -- by faulting the offending
+    /// process instead of panicking the CPU thread, mirroring `Cpu::fault`.
+    /// Leaves `trap_cause` set for the scheduler to read back via
+    /// `Cpu::take_trap_cause` once it observes `ProcessState::Faulted`.
+    fn trap(resources: &mut CpuResources, cause: TrapCause) {
+        resources.trap_cause = Some(cause);
+        Cpu::signal_interrupt(resources, ProcessState::Faulted);
     }
 
     fn get_physical_address_for(resources: &CpuResources, logical_address: usize) -> usize {
@@ -361,6 +1019,21 @@ impl Cpu {
         resources.registers[reg_num] = value;
     }
 
+    /// Derives `Flags` from an arithmetic op's `u32` result plus whether it
+    /// carried (from an unsigned `overflowing_*`) or overflowed (from the
+    /// same operands reinterpreted as `i32`), following the condition-code
+    /// model used by the m68k and WE32100 cores: `Zero`/`Negative` read the
+    /// result itself, while `Carry`/`Overflow` are flagged by the caller
+    /// since they depend on which operation produced the result.
+    fn compute_flags(result: u32, carry: bool, overflow: bool) -> Flags {
+        Flags {
+            zero: result == 0,
+            negative: (result as i32) < 0,
+            carry,
+            overflow,
+        }
+    }
+
     fn signal_interrupt(resources: &mut CpuResources, interrupt_type: ProcessState) {
         resources.proc_interrupt_type = interrupt_type;
 
@@ -376,6 +1049,21 @@ impl Drop for Cpu {
     fn drop(&mut self) {
         self.cycle_should_terminate.store(true, Ordering::Relaxed);
         self.dma_should_terminate.store(true, Ordering::Relaxed);
+
+        // The cycle thread may be parked in `cycle`'s condvar wait (e.g.
+        // between debugger steps) with nothing else left to wake it up --
+        // notify it so it can observe `cycle_should_terminate` and return
+        // instead of blocking `join` forever.
+        {
+            let resources = self.resources.lock().unwrap();
+            let (lock, condvar) = &*resources.proc_should_interrupt_condvar;
+            let _should_interrupt = lock.lock().unwrap();
+            condvar.notify_all();
+        }
+
+        if let Some(cycle_thread_handle) = self.cycle_thread_handle.take() {
+            cycle_thread_handle.join().unwrap();
+        }
         if let Some(dma_channel_handle) = self.dma_channel_handle.take() {
             dma_channel_handle.join().unwrap();
         }
@@ -383,43 +1071,176 @@ impl Drop for Cpu {
 }
 
 enum DmaCommand {
-    Fetch { address: usize, response_sender: mpsc::Sender<u32> },
-    Store { address: usize, value: u32, response_sender: mpsc::Sender<()> },
+    /// Issued for `RD`: the fetched value is written directly into
+    /// `process_id`'s own registers rather than sent back to whichever
+    /// process happens to be running when the DMA thread finishes, since
+    /// that may no longer be the requesting process.
+    IoFetch { address: usize, process_id: u32, reg_num: usize },
+    /// Issued for `WR`.
+    IoStore { address: usize, value: u32, process_id: u32 },
 }
 
-struct CpuResources {
+pub(crate) struct CpuResources {
     memory: Arc<RwLock<Memory>>,
     dma_sender: mpsc::Sender<DmaCommand>,
     cache: Vec<u32>,
+    decoded_cache: Vec<Option<DecodedInstruction>>,
     program_counter: usize,
     mem_start_address: usize,
+    current_process_id: u32,
     registers: [u32; 16],
+    flags: Flags,
     proc_should_interrupt_condvar: Arc<(Mutex<bool>, Condvar)>,
     proc_interrupt_type: ProcessState,
+    quantum: Option<usize>,
+    instructions_executed_in_quantum: usize,
+    trap_cause: Option<TrapCause>,
+    pending_syscall: Option<PendingSyscall>,
+    breakpoints: HashSet<usize>,
+    watchpoints: HashSet<usize>,
+    last_memory_access: Option<usize>,
+    breakpoint_cause: Option<BreakpointCause>,
+    single_step: bool,
+    bypass_breakpoint_once: bool,
+    opcode_table: [OpcodeHandler; OPCODE_TABLE_LEN],
+    trace: bool,
 }
 
 impl CpuResources {
-    pub fn new(memory: Arc<RwLock<Memory>>, dma_sender: mpsc::Sender<DmaCommand>) -> CpuResources {
+    fn new(memory: Arc<RwLock<Memory>>, dma_sender: mpsc::Sender<DmaCommand>, trace: bool) -> CpuResources {
         CpuResources {
             memory,
             dma_sender,
             cache: Vec::new(),
+            decoded_cache: Vec::new(),
             program_counter: 0,
             mem_start_address: 0,
+            current_process_id: 0,
             registers: [0; 16],
+            flags: Flags::default(),
             proc_should_interrupt_condvar: Arc::new((Mutex::new(true), Condvar::new())),
             proc_interrupt_type: ProcessState::Terminated,
+            quantum: None,
+            instructions_executed_in_quantum: 0,
+            trap_cause: None,
+            pending_syscall: None,
+            breakpoints: HashSet::new(),
+            watchpoints: HashSet::new(),
+            last_memory_access: None,
+            breakpoint_cause: None,
+            single_step: false,
+            bypass_breakpoint_once: false,
+            opcode_table: Cpu::default_opcode_table(),
+            trace,
         }
     }
 }
 
-struct DecodedInstruction {
-    instr_type: u8,
-    opcode: u8,
-    reg_1_num: usize,
-    reg_2_num: usize,
-    reg_3_num: usize,
-    address: usize,
+/// A point-in-time view of CPU state for a debugging front-end, returned
+/// by `Cpu::dump_state`. Not wired into `Driver` -- see that method's doc
+/// comment.
+#[allow(dead_code)]
+pub(crate) struct CpuStateSnapshot {
+    pub program_counter: usize,
+    pub registers: [u32; 16],
+    pub mem_start_address: usize,
+    pub next_instruction: Option<DecodedInstruction>,
+}
+
+/// What tripped a `ProcessState::Faulted` interrupt. Read back by the
+/// scheduler via `Cpu::take_trap_cause` so it can log (and terminate) the
+/// offending process without bringing down the CPU and DMA threads.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TrapCause {
+    /// Raised by the default opcode table slot for any `(instr_type, opcode)`
+    /// pair no handler has been registered for -- see `Cpu::register_opcode`.
+    IllegalInstruction,
+    DivideByZero,
+    AddressOutOfBounds,
+}
+
+/// Why `Cpu::run_quantum` stopped, mirroring the subset of `ProcessState`
+/// it can actually observe back from `await_process_interrupt`. Only
+/// consumed by `Scheduler`, which discards the `Faulted` cause -- see its
+/// own doc comment for why it isn't wired into `Driver` either.
+#[allow(dead_code)]
+pub(crate) enum RunOutcome {
+    /// The process executed `HLT`.
+    Halted,
+    /// The process issued an `RD`/`WR` and is now parked on the DMA
+    /// channel; see `Cpu::take_completed_io`.
+    Waiting,
+    /// The process issued a `TRAP`/`SWI`; see `Cpu::take_pending_syscall`.
+    SystemCall,
+    /// The process ran `max_instructions` without halting, blocking, or
+    /// trapping.
+    Preempted,
+    /// The process trapped; see `TrapCause` for why.
+    Faulted(TrapCause),
+}
+
+/// A `TRAP`/`SWI` request captured when `execute_trap` decodes a syscall
+/// instruction: the syscall number (read out of `reg_1_num`'s register)
+/// and the full register file at the point of the trap, so a kernel-side
+/// syscall handler has whatever argument registers it needs without
+/// racing a process that's since resumed and changed them.
+pub(crate) struct PendingSyscall {
+    pub number: u32,
+    pub registers: [u32; 16],
+}
+
+/// Why `cycle` raised its most recent `ProcessState::Breakpoint`
+/// interrupt -- an instruction breakpoint, a watched memory address the
+/// instruction that just ran touched, or a single step via `Cpu::step`.
+/// Read back (and cleared) by `Cpu::await_debug_stop` to build a
+/// `StopReason`. Not wired into `Driver` -- see `Cpu::set_breakpoint`.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy)]
+enum BreakpointCause {
+    Breakpoint,
+    Watchpoint(usize),
+    Step,
+}
+
+/// Why `Cpu::execute_process_debug`/`continue_execution` stopped --
+/// either of the two debugger-specific reasons above, or one of the
+/// ordinary ways a process leaves the CPU that `RunOutcome` already
+/// names for `run_quantum`. Not wired into `Driver` -- see `Cpu::set_breakpoint`.
+#[allow(dead_code)]
+pub(crate) enum StopReason {
+    /// The PC reached an address registered via `Cpu::set_breakpoint`.
+    Breakpoint,
+    /// The instruction that just ran read or wrote an address registered
+    /// via `Cpu::set_watchpoint`.
+    Watchpoint(usize),
+    /// A single `Cpu::step()` finished.
+    Stepped,
+    Halted,
+    Waiting,
+    SystemCall,
+    Preempted,
+    Faulted(TrapCause),
+}
+
+/// A debugger pause reported by `Cpu::execute_process_debug` or
+/// `Cpu::continue_execution`: where execution stopped, why, and the
+/// register file at that point. Not wired into `Driver` -- see
+/// `Cpu::set_breakpoint`.
+#[allow(dead_code)]
+pub(crate) struct DebugStop {
+    pub pc: usize,
+    pub reason: StopReason,
+    pub registers: [u32; 16],
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DecodedInstruction {
+    pub instr_type: u8,
+    pub opcode: u8,
+    pub reg_1_num: usize,
+    pub reg_2_num: usize,
+    pub reg_3_num: usize,
+    pub address: usize,
 }
 
 impl DecodedInstruction {
@@ -441,6 +1262,43 @@ mod tests {
 
     use crate::io::ProgramInfo;
 
+    /// Runs `pcb` to `Terminated`, resuming it across however many `RD`/`WR`
+    /// requests it issues along the way: `execute_rd`/`execute_wr` complete
+    /// asynchronously via the DMA channel and report `Waiting`, not a
+    /// finished job, so a test that wants final memory contents (like
+    /// `test_execute_job1`) can't just call `await_process_interrupt` once
+    /// the way it could when `RD`/`WR` blocked synchronously. This polls
+    /// `take_completed_io` the same way `test_execute_process_rd_completes_asynchronously_via_dma_channel`
+    /// does, then re-dispatches the process exactly like `ShortTermScheduler`
+    /// does once a waiting process comes off the DMA channel.
+    fn run_process_to_completion(cpu: &mut Cpu, pcb: Arc<Mutex<ProcessControlBlock>>) {
+        cpu.execute_process(Some(pcb.clone()), None, None);
+
+        loop {
+            match cpu.await_process_interrupt() {
+                ProcessState::Terminated => {
+                    cpu.execute_process(None, Some(pcb), None);
+                    return;
+                },
+                ProcessState::Waiting => {
+                    cpu.execute_process(None, Some(pcb.clone()), None);
+
+                    let process_id = pcb.lock().unwrap().get_id();
+                    loop {
+                        if cpu.take_completed_io().contains(&process_id) {
+                            break;
+                        }
+
+                        thread::sleep(Duration::from_millis(10));
+                    }
+
+                    cpu.execute_process(Some(pcb.clone()), None, None);
+                },
+                _ => panic!("run_process_to_completion only supports a job that halts or blocks on I/O"),
+            }
+        }
+    }
+
     #[test]
     fn test_execute_job1() {
         let program_info = ProgramInfo {
@@ -535,8 +1393,7 @@ mod tests {
         let memory = Arc::new(RwLock::new(memory));
         let mut cpu = Cpu::new(memory.clone());
     
-        cpu.execute_process(Some(pcb), None);
-        cpu.await_process_interrupt();
+        run_process_to_completion(&mut cpu, pcb);
     
         let program_data = {
             let memory = memory.read().unwrap();
@@ -745,8 +1602,7 @@ mod tests {
         let memory = Arc::new(RwLock::new(memory));
         let mut cpu = Cpu::new(memory.clone());
     
-        cpu.execute_process(Some(pcb), None);
-        cpu.await_process_interrupt();
+        run_process_to_completion(&mut cpu, pcb);
     
         let program_data = {
             let memory = memory.read().unwrap();
@@ -957,8 +1813,7 @@ mod tests {
         let memory = Arc::new(RwLock::new(memory));
         let mut cpu = Cpu::new(memory.clone());
     
-        cpu.execute_process(Some(pcb), None);
-        cpu.await_process_interrupt();
+        run_process_to_completion(&mut cpu, pcb);
     
         let program_data = {
             let memory = memory.read().unwrap();
@@ -1158,8 +2013,7 @@ mod tests {
         let memory = Arc::new(RwLock::new(memory));
         let mut cpu = Cpu::new(memory.clone());
     
-        cpu.execute_process(Some(pcb), None);
-        cpu.await_process_interrupt();
+        run_process_to_completion(&mut cpu, pcb);
     
         let program_data = {
             let memory = memory.read().unwrap();
@@ -1265,4 +2119,465 @@ mod tests {
             assert_eq!(program_data[i], expected_temp_data[i - 51]);
         }
     }
+
+    #[test]
+    fn test_execute_process_with_quantum_preempts_then_resumes() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 2,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 0x63; HLT
+        let program_data: [u32; 2] = [0x4B010063, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb.clone()), None, Some(1));
+        let preempted_state = cpu.await_process_interrupt();
+        assert!(matches!(preempted_state, ProcessState::Preempted));
+        assert_eq!(cpu.take_instructions_executed_in_quantum(), 1);
+        assert_eq!(pcb.lock().unwrap().registers[1], 0);
+
+        cpu.execute_process(Some(pcb.clone()), Some(pcb.clone()), None);
+        let terminated_state = cpu.await_process_interrupt();
+        assert!(matches!(terminated_state, ProcessState::Terminated));
+        assert_eq!(pcb.lock().unwrap().registers[1], 0x63);
+    }
+
+    #[test]
+    fn test_run_quantum_round_robins_two_processes_to_completion() {
+        let make_program_info = |id| ProgramInfo {
+            id,
+            priority: 1,
+            instruction_buffer_size: 4,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // NOOP; NOOP; NOOP; HLT.
+        let program_data: [u32; 4] = [0x13000000, 0x13000000, 0x13000000, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&make_program_info(1), &program_data);
+        memory.create_process(&make_program_info(2), &program_data);
+        let pcb_1 = memory.get_pcb_for(1);
+        let pcb_2 = memory.get_pcb_for(2);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        // Each process gets a 2-instruction slice, so both are cut off
+        // mid-burst once before finishing on their second slice --
+        // observable interleaving instead of one process running to
+        // completion before the other starts.
+        assert!(matches!(cpu.run_quantum(pcb_1.clone(), 2), RunOutcome::Preempted));
+        assert!(matches!(cpu.run_quantum(pcb_2.clone(), 2), RunOutcome::Preempted));
+        assert_eq!(pcb_1.lock().unwrap().program_counter, 2);
+        assert_eq!(pcb_2.lock().unwrap().program_counter, 2);
+
+        assert!(matches!(cpu.run_quantum(pcb_1.clone(), 2), RunOutcome::Halted));
+        assert!(matches!(cpu.run_quantum(pcb_2.clone(), 2), RunOutcome::Halted));
+    }
+
+    #[test]
+    fn test_execute_process_self_modifying_store_is_picked_up_on_next_dispatch() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 4,
+            in_buffer_size: 1,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        let program_data: [u32; 5] = [
+            0x43010010, // LW R1, 0x10 (loads the HLT word parked in the input buffer)
+            0x4210000C, // ST R1, 0xC  (overwrites word 3's NOOP with that HLT word)
+            0x13000000, // NOOP
+            0x13000000, // NOOP -- about to be overwritten by the ST above
+            0x92000000, // Data: a raw HLT encoding, not yet executed as code.
+        ];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        // First dispatch: the LW, ST, and first NOOP run and pre-empt
+        // right before the process would reach the instruction it just
+        // overwrote -- `decoded_cache` for that slot was pre-decoded as
+        // `NOOP` at the start of this very dispatch.
+        assert!(matches!(cpu.run_quantum(pcb.clone(), 3), RunOutcome::Preempted));
+        assert_eq!(pcb.lock().unwrap().program_counter, 3);
+
+        // Second dispatch: `execute_process` rebuilds `decoded_cache` from
+        // the now-modified instruction buffer, so the process halts on
+        // what used to be a `NOOP` instead of running it and trapping on
+        // the fetch past the end of its 4-word instruction buffer.
+        assert!(matches!(cpu.run_quantum(pcb.clone(), 10), RunOutcome::Halted));
+    }
+
+    #[test]
+    fn test_execute_process_blz_branches_on_negative_subtraction_result() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 6,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 1; MOVI R2, 2; SUB R1, R2, R3 (R3 = 1 - 2 = -1);
+        // BLZ R3, 0x14 (skips the trap below if taken); <invalid opcode>; HLT
+        let program_data: [u32; 6] = [0x4B010001, 0x4B020002, 0x06123000, 0x5A300014, 0x0F000000, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb), None, None);
+        let terminated_state = cpu.await_process_interrupt();
+
+        // Reaching HLT rather than the invalid-opcode trap in between
+        // confirms BLZ took the branch for a negative result.
+        assert!(matches!(terminated_state, ProcessState::Terminated));
+    }
+
+    #[test]
+    fn test_execute_process_bgz_does_not_branch_on_negative_register_value() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 4,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 1; MOVI R2, 2; SUB R1, R2, R3 (R3 = 1 - 2 = -1); BGZ R3, 0xC
+        let program_data: [u32; 4] = [0x4B010001, 0x4B020002, 0x06123000, 0x5930000C];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb), None, None);
+        let faulted_state = cpu.await_process_interrupt();
+
+        // Falling through to an out-of-bounds fetch confirms BGZ did not
+        // take the branch for a negative result.
+        assert!(matches!(faulted_state, ProcessState::Faulted));
+        assert!(matches!(cpu.take_trap_cause(), Some(TrapCause::AddressOutOfBounds)));
+    }
+
+    #[test]
+    fn test_execute_process_breakpoint_pauses_then_steps_past_it() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 3,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 1; MOVI R2, 2; HLT
+        let program_data: [u32; 3] = [0x4B010001, 0x4B020002, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.set_breakpoint(0x4);
+
+        cpu.execute_process(Some(pcb.clone()), None, None);
+        let breakpoint_state = cpu.await_process_interrupt();
+        assert!(matches!(breakpoint_state, ProcessState::Breakpoint));
+
+        let snapshot = cpu.dump_state();
+        assert_eq!(snapshot.program_counter, 1);
+        assert_eq!(snapshot.registers[1], 1);
+        let next_instruction = snapshot.next_instruction.unwrap();
+        assert_eq!(next_instruction.opcode, 0xB);
+        assert_eq!(next_instruction.reg_2_num, 2);
+
+        cpu.step();
+        let second_breakpoint_state = cpu.await_process_interrupt();
+        assert!(matches!(second_breakpoint_state, ProcessState::Breakpoint));
+        assert_eq!(cpu.dump_state().registers[2], 2);
+
+        cpu.clear_breakpoint(0x4);
+        cpu.step();
+        let terminated_state = cpu.await_process_interrupt();
+        assert!(matches!(terminated_state, ProcessState::Terminated));
+    }
+
+    #[test]
+    fn test_execute_process_debug_pauses_on_watchpoint_then_continues_to_halt() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 4,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 1,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 1; ST R1, 0x10 (writes into the temp buffer); MOVI R2, 2; HLT; temp buffer word.
+        let program_data: [u32; 5] = [0x4B010001, 0x42100010, 0x4B020002, 0x92000000, 0x00000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+        let watched_address = pcb.lock().unwrap().get_mem_temp_start_address();
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.set_watchpoint(watched_address);
+
+        let stop = cpu.execute_process_debug(pcb.clone());
+        assert_eq!(stop.pc, 2);
+        assert!(matches!(stop.reason, StopReason::Watchpoint(address) if address == watched_address));
+        assert_eq!(stop.registers[1], 1);
+
+        cpu.clear_watchpoint(watched_address);
+        let halted = cpu.continue_execution();
+        assert!(matches!(halted.reason, StopReason::Halted));
+        assert_eq!(halted.registers[2], 2);
+    }
+
+    #[test]
+    fn test_execute_process_rd_completes_asynchronously_via_dma_channel() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 2,
+            in_buffer_size: 1,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // RD R1, [8]; HLT
+        let program_data: [u32; 3] = [0xC0100008, 0x92000000, 0x0000002A];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb.clone()), None, None);
+        let waiting_state = cpu.await_process_interrupt();
+        assert!(matches!(waiting_state, ProcessState::Waiting));
+
+        // Write the process back off the CPU before waiting on its I/O,
+        // the same way a scheduler parks it in a waiting queue -- this is
+        // what lets the DMA thread's completion handler tell the PCB is
+        // now the only live copy of its registers (see `IoFetch` in
+        // `Cpu::new_with_trace`) instead of a copy that's about to be
+        // clobbered by this write-back.
+        cpu.execute_process(None, Some(pcb.clone()), None);
+
+        let mut completed_ids = Vec::new();
+        while completed_ids.is_empty() {
+            completed_ids = cpu.take_completed_io();
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(completed_ids, vec![1]);
+        assert_eq!(pcb.lock().unwrap().registers[1], 0x2A);
+    }
+
+    #[test]
+    fn test_execute_process_traps_on_divide_by_zero() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 2,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 5; DIVI R1, 0
+        let program_data: [u32; 2] = [0x4B010005, 0x4E010000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb), None, None);
+        let interrupt_type = cpu.await_process_interrupt();
+
+        assert!(matches!(interrupt_type, ProcessState::Faulted));
+        assert!(matches!(cpu.take_trap_cause(), Some(TrapCause::DivideByZero)));
+    }
+
+    #[test]
+    fn test_execute_process_traps_on_fetch_past_instruction_buffer() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // No-op, never halts, so the CPU fetches past the end of the
+        // process's 1-word instruction buffer on the next cycle.
+        let program_data: [u32; 1] = [0x13000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb), None, None);
+        let interrupt_type = cpu.await_process_interrupt();
+
+        assert!(matches!(interrupt_type, ProcessState::Faulted));
+        assert!(matches!(cpu.take_trap_cause(), Some(TrapCause::AddressOutOfBounds)));
+    }
+
+    #[test]
+    fn test_execute_process_trap_raises_syscall_then_resumes_without_reloading_cache() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 3,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // MOVI R1, 7; TRAP R1; HLT
+        let program_data: [u32; 3] = [0x4B010007, 0xC2100000, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb.clone()), None, None);
+        let syscall_state = cpu.await_process_interrupt();
+        assert!(matches!(syscall_state, ProcessState::SystemCall));
+
+        let pending_syscall = cpu.take_pending_syscall().unwrap();
+        assert_eq!(pending_syscall.number, 7);
+
+        cpu.resume_after_syscall(2, 0x99);
+        let terminated_state = cpu.await_process_interrupt();
+        assert!(matches!(terminated_state, ProcessState::Terminated));
+
+        cpu.execute_process(None, Some(pcb.clone()), None);
+        assert_eq!(pcb.lock().unwrap().registers[2], 0x99);
+    }
+
+    fn execute_custom_pseudo_op(resources: &mut CpuResources, instruction: &DecodedInstruction) {
+        Cpu::set_reg(resources, instruction.reg_1_num, 0xABCD);
+    }
+
+    #[test]
+    fn test_register_opcode_overrides_an_unmapped_slot() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 2,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // <custom I/O opcode 0x3> R1; HLT
+        let program_data: [u32; 2] = [0xC3100000, 0x92000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.register_opcode(0b11, 0x3, execute_custom_pseudo_op);
+
+        cpu.execute_process(Some(pcb.clone()), None, None);
+        let terminated_state = cpu.await_process_interrupt();
+        assert!(matches!(terminated_state, ProcessState::Terminated));
+
+        cpu.execute_process(None, Some(pcb.clone()), None);
+        assert_eq!(pcb.lock().unwrap().registers[1], 0xABCD);
+    }
+
+    #[test]
+    fn test_unmapped_opcode_traps_as_illegal_instruction() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        // <unmapped I/O opcode 0x3>
+        let program_data: [u32; 1] = [0xC3000000];
+
+        let mut memory = Memory::new();
+        memory.create_process(&program_info, &program_data);
+        let pcb = memory.get_pcb_for(1);
+
+        let memory = Arc::new(RwLock::new(memory));
+        let mut cpu = Cpu::new(memory.clone());
+
+        cpu.execute_process(Some(pcb), None, None);
+        let faulted_state = cpu.await_process_interrupt();
+
+        assert!(matches!(faulted_state, ProcessState::Faulted));
+        assert!(matches!(cpu.take_trap_cause(), Some(TrapCause::IllegalInstruction)));
+    }
 }
\ No newline at end of file
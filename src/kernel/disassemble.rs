@@ -0,0 +1,155 @@
+use std::fmt;
+
+/// A decoded view of a raw `u32` opcode, produced by `disassemble` for
+/// `Cpu::with_trace`'s per-step trace and any other debugging front-end
+/// that wants to show instructions instead of only post-mortem memory.
+/// Decodes independently of `Cpu::decode` -- this module only ever reads
+/// an instruction, it never executes one, so it can disassemble a job's
+/// whole instruction buffer up front as well as trace it one step at a
+/// time.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Instruction {
+    /// A recognized opcode: `mnemonic` plus the register/address fields
+    /// the encoding actually carries for it.
+    Known {
+        mnemonic: &'static str,
+        reg_1_num: usize,
+        reg_2_num: usize,
+        reg_3_num: usize,
+        address: usize,
+    },
+    /// No handler is registered for this `(instr_type, opcode)` pair --
+    /// mirrors `Cpu::execute_illegal_instruction`'s trap at runtime, but a
+    /// trace prints a line for it instead of aborting mid-run.
+    Unknown(u32),
+}
+
+/// Decodes `instruction` the same way the execute loop does: top byte is
+/// the opcode (instr_type in its top 2 bits, then a 6-bit opcode), followed
+/// by register fields and a 16-bit address/immediate whose layout depends
+/// on instr_type -- see the match below.
+pub(crate) fn disassemble(instruction: u32) -> Instruction {
+    let instr_type: u8 = extract_bits(instruction, 0, 2).try_into().unwrap();
+    let opcode: u8 = extract_bits(instruction, 2, 6).try_into().unwrap();
+
+    let mnemonic = match mnemonic_for(instr_type, opcode) {
+        Some(mnemonic) => mnemonic,
+        None => return Instruction::Unknown(instruction),
+    };
+
+    let (reg_1_num, reg_2_num, reg_3_num, address) = match instr_type {
+        0b00 => /* Arithmetic */ (
+            extract_bits(instruction, 8, 4) as usize,
+            extract_bits(instruction, 12, 4) as usize,
+            extract_bits(instruction, 16, 4) as usize,
+            0,
+        ),
+        0b01 => /* Conditional branch or immediate */ (
+            extract_bits(instruction, 8, 4) as usize,
+            extract_bits(instruction, 12, 4) as usize,
+            0,
+            extract_bits(instruction, 16, 16) as usize,
+        ),
+        0b10 => /* Unconditional jump */ (
+            0,
+            0,
+            0,
+            extract_bits(instruction, 8, 16) as usize,
+        ),
+        0b11 => /* IO */ (
+            extract_bits(instruction, 8, 4) as usize,
+            extract_bits(instruction, 12, 4) as usize,
+            0,
+            extract_bits(instruction, 16, 16) as usize,
+        ),
+        _ => unreachable!("instr_type is 2 bits"),
+    };
+
+    Instruction::Known { mnemonic, reg_1_num, reg_2_num, reg_3_num, address }
+}
+
+fn extract_bits(instruction: u32, start_index: u32, length: u32) -> u32 {
+    (instruction << start_index) >> (32 - length)
+}
+
+/// Mirrors `Cpu::default_opcode_table`'s mapping from `(instr_type, opcode)`
+/// to an instruction, but as a mnemonic instead of a handler -- opcodes
+/// registered later via `Cpu::register_opcode` have no mnemonic here and
+/// disassemble as `Instruction::Unknown`.
+fn mnemonic_for(instr_type: u8, opcode: u8) -> Option<&'static str> {
+    if opcode == 0x13 {
+        return Some("NOOP");
+    }
+
+    match (instr_type, opcode) {
+        (0b00, 0x4) => Some("MOV"),
+        (0b00, 0x5) => Some("ADD"),
+        (0b00, 0x6) => Some("SUB"),
+        (0b00, 0x7) => Some("MUL"),
+        (0b00, 0x8) => Some("DIV"),
+        (0b00, 0x9) => Some("AND"),
+        (0b00, 0xA) => Some("OR"),
+        (0b00, 0x10) => Some("SLT"),
+
+        (0b01, 0x2) => Some("ST"),
+        (0b01, 0x3) => Some("LW"),
+        (0b01, 0xB) => Some("MOVI"),
+        (0b01, 0xC) => Some("ADDI"),
+        (0b01, 0xD) => Some("MULI"),
+        (0b01, 0xE) => Some("DIVI"),
+        (0b01, 0xF) => Some("LDI"),
+        (0b01, 0x11) => Some("SLTI"),
+        (0b01, 0x15) => Some("BEQ"),
+        (0b01, 0x16) => Some("BNE"),
+        (0b01, 0x17) => Some("BEZ"),
+        (0b01, 0x18) => Some("BNZ"),
+        (0b01, 0x19) => Some("BGZ"),
+        (0b01, 0x1A) => Some("BLZ"),
+
+        (0b10, 0x12) => Some("HLT"),
+        (0b10, 0x14) => Some("JMP"),
+
+        (0b11, 0x0) => Some("RD"),
+        (0b11, 0x1) => Some("WR"),
+        (0b11, 0x2) => Some("TRAP"),
+
+        _ => None,
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Formats a `ST`/`LW`/`RD`/`WR` that addresses memory directly through
+    /// register 0 (the accumulator, per `Cpu::execute_st`'s convention) with
+    /// the raw address, and one that addresses memory through a pointer
+    /// register with that register instead, so the effective address mode
+    /// is visible in the trace rather than always printing the raw 16-bit
+    /// field.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Instruction::Known { mnemonic, reg_1_num, reg_2_num, reg_3_num, address } => match mnemonic {
+                "MOV" => write!(f, "{} R{},R{}", mnemonic, reg_1_num, reg_2_num),
+                "ADD" | "SUB" | "MUL" | "DIV" | "AND" | "OR" | "SLT" =>
+                    write!(f, "{} R{},R{},R{}", mnemonic, reg_1_num, reg_2_num, reg_3_num),
+
+                "ST" | "RD" | "WR" => match reg_2_num {
+                    0 => write!(f, "{} R{},0x{:X}", mnemonic, reg_1_num, address),
+                    _ => write!(f, "{} R{},R{}", mnemonic, reg_1_num, reg_2_num),
+                },
+                "LW" => match reg_1_num {
+                    0 => write!(f, "{} R{},0x{:X}", mnemonic, reg_2_num, address),
+                    _ => write!(f, "{} R{},R{}", mnemonic, reg_2_num, reg_1_num),
+                },
+                "MOVI" | "ADDI" | "MULI" | "DIVI" | "LDI" =>
+                    write!(f, "{} R{},0x{:X}", mnemonic, reg_2_num, address),
+                "SLTI" => write!(f, "{} R{},R{},0x{:X}", mnemonic, reg_1_num, reg_2_num, address),
+                "BEQ" | "BNE" => write!(f, "{} R{},R{},0x{:X}", mnemonic, reg_1_num, reg_2_num, address),
+                "BEZ" | "BNZ" | "BGZ" | "BLZ" => write!(f, "{} R{},0x{:X}", mnemonic, reg_1_num, address),
+                "JMP" => write!(f, "{} 0x{:X}", mnemonic, address),
+                "TRAP" => write!(f, "{} R{}", mnemonic, reg_1_num),
+                "HLT" | "NOOP" => write!(f, "{}", mnemonic),
+                _ => unreachable!("mnemonic_for only produces mnemonics handled above"),
+            },
+            Instruction::Unknown(raw) => write!(f, "UNKNOWN 0x{:08X}", raw),
+        }
+    }
+}
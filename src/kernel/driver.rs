@@ -3,45 +3,159 @@ use std::rc::Rc;
 use std::sync::{Arc, Mutex, RwLock};
 
 use super::*;
+use super::cpu::OpcodeHandler;
 
-use crate::io::{Disk, loader};
-use crate::io::disk;
+use crate::io::{checkpoint, Disk, loader, stats_report};
+use crate::io::config::{Config, SchedulingAlgConfig, DEFAULT_CONFIG_PATH};
 
 pub struct Driver {
-    _cpu: Arc<Mutex<Cpu>>,
+    /// Only read by `register_opcode`, which forwards to every core --
+    /// see that method's doc comment for why nothing calls it yet.
+    #[allow(dead_code)]
+    cpus: Vec<Arc<Mutex<Cpu>>>,
     disk: Rc<RefCell<Disk>>,
     memory: Arc<RwLock<Memory>>,
     lts: LongTermScheduler,
     sts: ShortTermScheduler,
+    config: Config,
 }
 
 impl Driver {
     pub fn new() -> Driver {
+        let config = Config::load(DEFAULT_CONFIG_PATH).unwrap_or_else(|err| {
+            println!("Failed to load config from '{}', falling back to defaults: {}", DEFAULT_CONFIG_PATH, err);
+            Config::default()
+        });
+
+        Driver::with_config(config)
+    }
+
+    fn with_config(config: Config) -> Driver {
         let disk = Rc::new(RefCell::new(Disk::new()));
-        let memory = Arc::new(RwLock::new(Memory::new()));
-        let cpu = Arc::new(Mutex::new(Cpu::new(memory.clone())));
-        
+        let memory = Arc::new(RwLock::new(Memory::with_size(config.memory_size)));
+        let cpus: Vec<Arc<Mutex<Cpu>>> = (0..config.num_cores)
+            .map(|_| Arc::new(Mutex::new(Cpu::new(memory.clone()))))
+            .collect();
+
         let disk_clone = disk.clone();
         let memory_clone = memory.clone();
-        let cpu_clone = cpu.clone();
+        let sts_memory_clone = memory.clone();
+        let cpus_clone = cpus.clone();
+        let scheduling_alg = Driver::to_sts_scheduling_alg(&config.scheduling_alg);
 
         Driver {
-            _cpu: cpu,
+            cpus,
             disk,
             memory,
             lts: LongTermScheduler::new(disk_clone, memory_clone),
-            sts: ShortTermScheduler::new(cpu_clone, StsSchedulingAlg::Fifo),
-            // sts: ShortTermScheduler::new(cpu_clone, StsSchedulingAlg::Priority),
+            sts: ShortTermScheduler::new(cpus_clone, sts_memory_clone, scheduling_alg),
+            config,
+        }
+    }
+
+    fn to_sts_scheduling_alg(config: &SchedulingAlgConfig) -> StsSchedulingAlg {
+        match config {
+            SchedulingAlgConfig::Fifo => StsSchedulingAlg::Fifo,
+            SchedulingAlgConfig::Priority { aging_interval_ms } => StsSchedulingAlg::Priority { aging_interval_ms: *aging_interval_ms },
+            SchedulingAlgConfig::RoundRobin { quantum } => StsSchedulingAlg::RoundRobin { quantum: *quantum },
+        }
+    }
+
+    /// Registers `handler` for `(instr_type, opcode)` on every core, so a
+    /// caller outside `Cpu` can extend the instruction set (a custom I/O
+    /// device, an additional syscall, alternative arithmetic) without
+    /// touching `Cpu`'s own dispatch table -- see `Cpu::register_opcode`.
+    /// Not wired into `start`/`resume` -- no opcode table extension has a
+    /// caller yet, but the hook is meant to be used this way once one does.
+    #[allow(dead_code)]
+    pub fn register_opcode(&self, instr_type: u8, opcode: u8, handler: OpcodeHandler) {
+        for cpu in &self.cpus {
+            cpu.lock().unwrap().register_opcode(instr_type, opcode, handler);
+        }
+    }
+
+    /// Serializes the full live simulator state -- the disk's program
+    /// catalog, every resident process's memory and PCB state, and what's
+    /// still pending in the LTS/STS queues -- to a single checkpoint file
+    /// via `io::checkpoint`.
+    ///
+    /// Only takes a consistent snapshot when called between batches, the
+    /// same point `start` calls it from (every `config.checkpoint_interval_batches`th
+    /// batch): nothing is actively dispatched on a core there, so there's
+    /// no in-flight register state to race with.
+    pub fn checkpoint(&self, path: &str) -> std::io::Result<()> {
+        let disk_programs = self.disk.borrow().all_programs();
+        let processes = self.memory.read().unwrap().snapshot();
+        let (ready_ids, waiting_ids) = self.sts.snapshot_queue_ids();
+        let pending_ids = self.lts.pending_program_ids();
+
+        checkpoint::write_checkpoint(path, &checkpoint::Checkpoint {
+            disk_programs,
+            processes,
+            scheduler: checkpoint::SchedulerSnapshot { ready_ids, waiting_ids, pending_ids },
+        })
+    }
+
+    /// Rebuilds a `Driver` from a file written by `checkpoint`: restores
+    /// the disk catalog and resident processes exactly as they were, then
+    /// re-enqueues whatever was still ready/waiting/pending so the batch
+    /// can continue from there. Core count and memory size come from the
+    /// same config `Driver::new` would load; the scheduling algorithm
+    /// doesn't, since a checkpoint doesn't record which one the original
+    /// run was using, and re-deriving queue order from raw ready/waiting
+    /// ids is only meaningful under `Fifo`.
+    pub fn restore(path: &str) -> std::io::Result<Driver> {
+        let config = Config::load(DEFAULT_CONFIG_PATH).unwrap_or_else(|err| {
+            println!("Failed to load config from '{}', falling back to defaults: {}", DEFAULT_CONFIG_PATH, err);
+            Config::default()
+        });
+
+        let checkpoint = checkpoint::restore_checkpoint(path)?;
+
+        let mut disk = Disk::new();
+        for (program_info, data) in checkpoint.disk_programs {
+            disk.write_program(program_info, &data);
+        }
+        let disk = Rc::new(RefCell::new(disk));
+
+        let mut memory = Memory::with_size(config.memory_size);
+        memory.restore_processes(checkpoint.processes);
+        let memory = Arc::new(RwLock::new(memory));
+
+        let cpus: Vec<Arc<Mutex<Cpu>>> = (0..config.num_cores)
+            .map(|_| Arc::new(Mutex::new(Cpu::new(memory.clone()))))
+            .collect();
+
+        let disk_clone = disk.clone();
+        let memory_clone = memory.clone();
+        let cpus_clone = cpus.clone();
+
+        let mut lts = LongTermScheduler::new(disk_clone, memory_clone);
+        lts.enqueue_programs(checkpoint.scheduler.pending_ids);
+
+        let mut sts = ShortTermScheduler::new(cpus_clone, memory.clone(), StsSchedulingAlg::Fifo);
+        for process_id in checkpoint.scheduler.ready_ids.into_iter().chain(checkpoint.scheduler.waiting_ids) {
+            let pcb = memory.read().unwrap().get_pcb_for(process_id);
+            sts.schedule_process(pcb);
         }
+
+        Ok(Driver {
+            cpus,
+            disk,
+            memory,
+            lts,
+            sts,
+            config,
+        })
     }
 
     pub fn start(&mut self) {
         println!("Starting the driver.");
         println!("Loading programs into disk.");
-        let program_ids = loader::load_programs_into_disk(&mut self.disk.borrow_mut())
+        let program_ids = loader::load_programs_into_disk(&mut self.disk.borrow_mut(), &self.config.program_file_path)
             .unwrap_or_else(|err| {
                 println!("Failed to load programs into disk: {}", err);
-                return Vec::new();
+                Vec::new()
             });
 
         if program_ids.is_empty() {
@@ -49,11 +163,40 @@ impl Driver {
             return;
         }
 
+        let largest_program_len = self.disk.borrow().all_programs().into_iter()
+            .map(|(_, data)| data.len())
+            .max()
+            .unwrap_or(0);
+
+        if largest_program_len > self.config.memory_size {
+            println!(
+                "Configured memory_size ({}) is smaller than the largest loaded program ({} words); aborting.",
+                self.config.memory_size, largest_program_len,
+            );
+            return;
+        }
+
         println!("Enqueuing programs into LTS.");
         self.lts.enqueue_programs(program_ids);
 
+        self.run_batches();
+    }
+
+    /// Resumes a simulation restored by `Driver::restore`: waits for
+    /// whatever `restore` already dispatched to the STS to finish, then
+    /// falls into the same batch loop `start` runs, for any programs that
+    /// were still pending in the LTS when the checkpoint was taken.
+    pub fn resume(&mut self) {
+        println!("Resuming from checkpoint.");
+        self.sts.await_all_procs_finished();
+        self.run_batches();
+    }
+
+    fn run_batches(&mut self) {
         println!("Starting the LTS.");
+        let mut batch_num = 0;
         while self.lts.has_programs() {
+            batch_num += 1;
             let process_ids = self.lts.batch_step();
             let num_processes = process_ids.len();
 
@@ -67,11 +210,97 @@ impl Driver {
             println!("Awaiting all processes to finish.");
             self.sts.await_all_procs_finished();
 
-            println!("Dumped memory for {} processes after completion.", num_processes);
-            self.memory.write().unwrap().core_dump(self.disk);
-            // TODO: Update disk using contents of dumped memory.
+            let stats = SchedulerStats::collect(&self.memory.read().unwrap().get_pcbs(true));
+            println!(
+                "Batch stats: {} processes, avg turnaround {:.3}ms, avg wait {:.3}ms, avg I/O wait {:.3}ms, {} context switches, {} I/O requests, {:.1}% CPU utilization.",
+                stats.num_processes,
+                stats.avg_turnaround_time_ms,
+                stats.avg_wait_time_ms,
+                stats.avg_io_wait_time_ms,
+                stats.total_context_switches,
+                stats.total_io_requests,
+                stats.cpu_utilization * 100.0,
+            );
+
+            let scheduler_stats_report_path = format!("{}/scheduler_stats.txt", self.config.out_path);
+            stats_report::append_report(&scheduler_stats_report_path, &stats.to_report(self.sts.alg_label()))
+                .unwrap_or_else(|err| {
+                    println!("Failed to write scheduler stats report: {}", err);
+                });
+
+            println!("Unloading {} finished processes back to disk.", num_processes);
+            let core_dump_path = format!("{}/core_dump.txt", self.config.out_path);
+            self.lts.unload_all(&core_dump_path);
+
+            if let Some(interval) = self.config.checkpoint_interval_batches {
+                if interval > 0 && batch_num % interval == 0 {
+                    let checkpoint_path = format!("{}/checkpoint.txt", self.config.out_path);
+                    println!("Writing checkpoint after batch {}.", batch_num);
+                    self.checkpoint(&checkpoint_path).unwrap_or_else(|err| {
+                        println!("Failed to write checkpoint: {}", err);
+                    });
+                }
+            }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::ProgramInfo;
+
+    #[test]
+    fn test_driver_restore_resumes_a_checkpointed_process_to_completion() {
+        let path = "test_driver_restore.tmp";
+
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        checkpoint::write_checkpoint(path, &checkpoint::Checkpoint {
+            disk_programs: Vec::new(),
+            processes: vec![checkpoint::ProcessSnapshot {
+                program_info,
+                mem_start_address: 0,
+                mem_end_address: 1,
+                program_counter: 0,
+                registers: [0; 16],
+                state_tag: 0,
+                turnaround_time_ms: 0.0,
+                burst_times_ms: Vec::new(),
+                wait_time_ms: 0.0,
+                io_wait_time_ms: 0.0,
+                context_switch_count: 0,
+                io_request_count: 0,
+                enqueued_at_ns: 0,
+                instructions_executed_count: 0,
+                data: vec![0x92000000], // HLT
+            }],
+            scheduler: checkpoint::SchedulerSnapshot { ready_ids: vec![1], waiting_ids: Vec::new(), pending_ids: Vec::new() },
+        }).unwrap();
+
+        let restored_driver = Driver::restore(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        restored_driver.sts.await_all_procs_finished();
+
+        let pcb = restored_driver.memory.read().unwrap().get_pcb_for(1);
+        assert!(matches!(pcb.lock().unwrap().state, ProcessState::Terminated));
+
+        let roundtrip_path = "test_driver_restore_roundtrip.tmp";
+        restored_driver.checkpoint(roundtrip_path).unwrap();
+        let reparsed = checkpoint::restore_checkpoint(roundtrip_path).unwrap();
+        std::fs::remove_file(roundtrip_path).unwrap();
 
-        // TODO: Implement writing disk to file. Should be same format as program_file.txt. Make a module in io for it.
+        assert_eq!(reparsed.processes.len(), 1);
+        assert_eq!(reparsed.processes[0].state_tag, ProcessState::Terminated.as_tag());
     }
 }
\ No newline at end of file
@@ -0,0 +1,79 @@
+const NUM_IRQS: usize = 8;
+
+/// The IRQ line raised when an I/O request against a process's buffers
+/// completes.
+pub(crate) const IO_COMPLETE_IRQ: usize = 0;
+
+/// A fixed set of IRQ lines, each with a pending bit set by `raise`. `claim`
+/// hands back the lowest-numbered pending line without clearing it;
+/// `complete` clears the pending bit once a handler has serviced it. This
+/// lets the scheduler react to asynchronous device/I-O completion instead
+/// of only stepping processes synchronously. There's currently only one
+/// live IRQ source (`IO_COMPLETE_IRQ`), so this doesn't yet arbitrate
+/// between lines by priority or enable state the way a real GIC would --
+/// just enough to route that one line through a single, consistent
+/// claim/complete protocol.
+pub(crate) struct InterruptController {
+    pending: [bool; NUM_IRQS],
+}
+
+impl InterruptController {
+    pub fn new() -> InterruptController {
+        InterruptController { pending: [false; NUM_IRQS] }
+    }
+
+    pub fn raise(&mut self, irq: usize) {
+        self.pending[irq] = true;
+    }
+
+    /// Returns the lowest-numbered pending IRQ line, if any. Does not clear
+    /// the pending bit; call `complete` once the interrupt has been handled.
+    pub fn claim(&self) -> Option<usize> {
+        self.pending.iter().position(|&pending| pending)
+    }
+
+    pub fn complete(&mut self, irq: usize) {
+        self.pending[irq] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_returns_lowest_pending_line() {
+        let mut controller = InterruptController::new();
+        controller.raise(3);
+        controller.raise(1);
+
+        assert_eq!(controller.claim(), Some(1));
+    }
+
+    #[test]
+    fn test_claim_returns_none_when_nothing_pending() {
+        let controller = InterruptController::new();
+
+        assert_eq!(controller.claim(), None);
+    }
+
+    #[test]
+    fn test_complete_clears_pending_bit() {
+        let mut controller = InterruptController::new();
+        controller.raise(1);
+        controller.complete(1);
+
+        assert_eq!(controller.claim(), None);
+    }
+
+    #[test]
+    fn test_io_complete_irq_claim_roundtrip() {
+        let mut controller = InterruptController::new();
+        controller.raise(IO_COMPLETE_IRQ);
+
+        assert_eq!(controller.claim(), Some(IO_COMPLETE_IRQ));
+
+        controller.complete(IO_COMPLETE_IRQ);
+        assert_eq!(controller.claim(), None);
+    }
+}
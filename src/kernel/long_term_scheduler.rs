@@ -5,7 +5,7 @@ use std::sync::{Arc, RwLock};
 
 use super::Memory;
 
-use crate::io::Disk;
+use crate::io::{core_dump, Disk, ProgramInfo};
 
 pub(crate) struct LongTermScheduler {
     disk: Rc<RefCell<Disk>>,
@@ -33,7 +33,7 @@ impl LongTermScheduler {
         let disk = self.disk.borrow();
 
         let program_info = disk.get_info_for(program_id);
-        let program_data = disk.read_data_for(&program_info);
+        let program_data = disk.read_data_for(program_info);
 
         let mut memory = self.memory.write().unwrap();
 
@@ -62,26 +62,54 @@ impl LongTermScheduler {
         process_ids
     }
 
-    pub fn unload_all(&mut self) {
+    /// Writes each unloaded process's final instruction/data buffers back
+    /// to disk, then reclaims its memory region via `Memory::free_process`
+    /// so the freed hole can be reused by the next `step`, instead of
+    /// wiping every resident process out of memory at once. `core_dump_path`
+    /// is also where a self-describing hex dump of those buffers is left
+    /// for inspection, the same artifact `Memory::core_dump` used to write.
+    pub fn unload_all(&mut self, core_dump_path: &str) {
         let mut memory = self.memory.write().unwrap();
+        let mut disk = self.disk.borrow_mut();
 
-        for program_id in self.unload_list.iter() {
-            let pcb = memory.get_pcb_for(*program_id);
-            let pcb = pcb.lock().unwrap();
+        let mut programs = Vec::with_capacity(self.unload_list.len());
 
-            let data = memory.read_block_from(pcb.get_mem_out_start_address(), pcb.get_mem_end_address());
-            let mut disk = self.disk.borrow_mut();
+        for program_id in self.unload_list.drain(..) {
+            let pcb = memory.get_pcb_for(program_id);
+            let pcb = pcb.lock().unwrap();
 
-            disk.update_program(*program_id, &data);
+            let program_info = ProgramInfo::new(
+                pcb.get_id(),
+                pcb.get_priority(),
+                pcb.get_mem_in_start_address() - pcb.get_mem_start_address(),
+                pcb.get_mem_out_start_address() - pcb.get_mem_in_start_address(),
+                pcb.get_mem_temp_start_address() - pcb.get_mem_out_start_address(),
+                pcb.get_mem_end_address() - pcb.get_mem_temp_start_address(),
+            );
+            let data = memory.read_block_from(pcb.get_mem_start_address(), pcb.get_mem_end_address());
+            drop(pcb);
+
+            disk.write_program(program_info.clone(), &data);
+            memory.free_process(program_id);
+
+            programs.push((program_info, data));
         }
 
-        self.unload_list.clear();
-        memory.core_dump();
+        core_dump::write_core_dump(core_dump_path, &programs).unwrap_or_else(|err| {
+            println!("Failed to write core dump: {}", err);
+        });
     }
 
     pub fn has_programs(&self) -> bool {
         !self.program_queue.is_empty()
     }
+
+    /// Ids of programs still queued to be loaded into memory, in the
+    /// order they'll be loaded. Used by `Driver::checkpoint` to persist
+    /// this scheduler's pending work.
+    pub fn pending_program_ids(&self) -> Vec<u32> {
+        self.program_queue.iter().cloned().collect()
+    }
 }
 
 #[cfg(test)]
@@ -94,7 +122,7 @@ mod tests {
     fn test_long_term_scheduler_enqueue_then_step() {
         let mut disk = Disk::new();
 
-        disk.write_program(20, 1, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(20, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
         
         let disk = Rc::new(RefCell::new(disk));
         let memory = Arc::new(RwLock::new(Memory::new()));
@@ -110,8 +138,8 @@ mod tests {
     fn test_long_term_scheduler_enqueue_then_batch_step() {
         let mut disk = Disk::new();
 
-        disk.write_program(20, 1, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
-        disk.write_program(21, 1, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(20, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(21, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
         
         let disk = Rc::new(RefCell::new(disk));
         let memory = Arc::new(RwLock::new(Memory::new()));
@@ -130,8 +158,8 @@ mod tests {
 
         let program_data = vec![1; memory.get_remaining_memory() - 1];
         
-        disk.write_program(1, 1, program_data.len() - 3, 1, 1, 1, &program_data.as_slice());
-        disk.write_program(2, 1, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(1, 1, program_data.len() - 3, 1, 1, 1), program_data.as_slice());
+        disk.write_program(ProgramInfo::new(2, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
         
         let disk = Rc::new(RefCell::new(disk));
         let memory = Arc::new(RwLock::new(memory));
@@ -162,8 +190,8 @@ mod tests {
 
         let program_data = vec![1; memory.get_remaining_memory() - 1];
 
-        disk.write_program(1, 1, program_data.len() - 3, 1, 1, 1, &program_data.as_slice());
-        disk.write_program(2, 1, 1, 1, 1, 2, &[1, 2, 3, 4, 5]);
+        disk.write_program(ProgramInfo::new(1, 1, program_data.len() - 3, 1, 1, 1), program_data.as_slice());
+        disk.write_program(ProgramInfo::new(2, 1, 1, 1, 1, 2), &[1, 2, 3, 4, 5]);
 
         let disk = Rc::new(RefCell::new(disk));
         let memory = Arc::new(RwLock::new(memory));
@@ -174,18 +202,32 @@ mod tests {
 
         assert_eq!(process_ids, vec![1]);
 
-        lts.unload_all();
+        let core_dump_path = "test_long_term_scheduler_batch_step_not_enough_memory_core_dump.tmp";
+        lts.unload_all(core_dump_path);
+        std::fs::remove_file(core_dump_path).unwrap();
+
         let process_ids = lts.batch_step();
 
         assert_eq!(process_ids, vec![2]);
     }
 
+    #[test]
+    fn test_long_term_scheduler_pending_program_ids() {
+        let disk = Rc::new(RefCell::new(Disk::new()));
+        let memory = Arc::new(RwLock::new(Memory::new()));
+        let mut lts = LongTermScheduler::new(disk, memory);
+
+        lts.enqueue_programs(vec![20, 21]);
+
+        assert_eq!(lts.pending_program_ids(), vec![20, 21]);
+    }
+
     #[test]
     fn test_long_term_scheduler_unload_all() {
         let mut disk = Disk::new();
         let memory = Memory::new();
 
-        disk.write_program(1, 1, 1, 1, 1, 2, &[0, 0, 0, 0, 0]);
+        disk.write_program(ProgramInfo::new(1, 1, 1, 1, 1, 2), &[0, 0, 0, 0, 0]);
 
         let disk = Rc::new(RefCell::new(disk));
         let memory = Arc::new(RwLock::new(memory));
@@ -203,11 +245,13 @@ mod tests {
             memory.write_to(pcb.get_mem_temp_start_address(), 5);
         }
 
-        lts.unload_all();
+        let core_dump_path = "test_long_term_scheduler_unload_all_core_dump.tmp";
+        lts.unload_all(core_dump_path);
+        std::fs::remove_file(core_dump_path).unwrap();
 
         let disk = disk.borrow();
         let program_info = disk.get_info_for(1);
-        let data = disk.read_data_for(&program_info);
+        let data = disk.read_data_for(program_info);
 
         assert_eq!(data, &[0, 0, 5, 5, 0]);
     }
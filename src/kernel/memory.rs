@@ -1,31 +1,56 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
-use super::ProcessControlBlock;
-
-use crate::io::{Disk, ProgramInfo};
-use crate::io::disk;
+use super::{ProcessControlBlock, ProcessState};
 
+use crate::io::{checkpoint, ProgramInfo};
 
+/// Default size for `Memory::new`, a test-only convenience now that
+/// `Driver::new` always builds via `with_size`, sized from `io::config::Config`.
+#[cfg(test)]
 const MEMORY_SIZE: usize = 1024;
 
+/// Raised when a process attempts to read or write an address outside of
+/// its own `[mem_start, mem_end)` region.
+#[derive(Debug, PartialEq)]
+pub(crate) struct MemoryFault {
+    pub process_id: u32,
+    pub addr: usize,
+}
+
+/// Callers share one instance behind an `Arc<RwLock<Memory>>` so multiple
+/// CPU cores can run processes concurrently. Reads already proceed without
+/// blocking each other under the `RwLock`, even across different
+/// processes' `mem_start_address..mem_end_address` ranges; a write still
+/// takes the whole lock, so two cores writing to disjoint regions at the
+/// same time still serialize. Splitting `data` into per-region locks would
+/// remove that, but isn't done here.
 pub(crate) struct Memory {
     pcb_map: HashMap<u32, Arc<Mutex<ProcessControlBlock>>>,
-    data: [u32; MEMORY_SIZE],
-    current_data_idx: usize,
+    data: Vec<u32>,
+    free_list: Vec<(usize, usize)>,
 }
 
 impl Memory {
+    /// Test-only shorthand for `with_size(MEMORY_SIZE)` -- production always
+    /// goes through `with_size` directly so it can be sized from config.
+    #[cfg(test)]
     pub fn new() -> Memory {
+        Memory::with_size(MEMORY_SIZE)
+    }
+
+    /// Builds a `Memory` of `size` words instead of the default
+    /// `MEMORY_SIZE`, so `Driver::new` can size it from `io::config::Config`.
+    pub fn with_size(size: usize) -> Memory {
         Memory {
             pcb_map: HashMap::new(),
-            data: [0; MEMORY_SIZE],
-            current_data_idx: 0,
+            data: vec![0; size],
+            free_list: vec![(0, size)],
         }
     }
 
     pub fn read_from(&self, address: usize) -> u32 {
-        if address >= MEMORY_SIZE {
+        if address >= self.data.len() {
             panic!("Out of bounds memory access. Address is greater than memory size");
         }
 
@@ -33,7 +58,7 @@ impl Memory {
     }
 
     pub fn read_block_from(&self, start_address: usize, end_address: usize) -> Vec<u32> {
-        if start_address >= MEMORY_SIZE || end_address >= MEMORY_SIZE {
+        if start_address >= self.data.len() || end_address >= self.data.len() {
             panic!("Out of bounds memory access. Start or end address is greater than memory size");
         } else if start_address > end_address {
             panic!("Invalid memory range. Start address is greater than end address");
@@ -43,18 +68,50 @@ impl Memory {
     }
 
     pub fn write_to(&mut self, address: usize, value: u32) {
-        if address >= MEMORY_SIZE {
+        if address >= self.data.len() {
             panic!("Out of bounds memory access");
         }
 
         self.data[address] = value;
     }
 
+    /// Reads `address` on behalf of `process_id`, faulting instead of
+    /// panicking if the address falls outside that process's allocated
+    /// `[mem_start, mem_end)` region.
+    pub fn read_for(&self, process_id: u32, address: usize) -> Result<u32, MemoryFault> {
+        self.validate_bounds(process_id, address)?;
+        Ok(self.read_from(address))
+    }
+
+    /// Writes `value` to `address` on behalf of `process_id`, faulting
+    /// instead of panicking if the address falls outside that process's
+    /// allocated `[mem_start, mem_end)` region.
+    pub fn write_for(&mut self, process_id: u32, address: usize, value: u32) -> Result<(), MemoryFault> {
+        self.validate_bounds(process_id, address)?;
+        self.write_to(address, value);
+        Ok(())
+    }
+
+    /// Checks `address` against `process_id`'s allocated region without
+    /// performing the read/write, so a caller that has to hand the address
+    /// off to something else (e.g. `Cpu::execute_rd`/`execute_wr` dispatching
+    /// to the DMA thread) can reject an out-of-bounds access up front.
+    pub(crate) fn validate_bounds(&self, process_id: u32, address: usize) -> Result<(), MemoryFault> {
+        let pcb = self.get_pcb_for(process_id);
+        let pcb = pcb.lock().unwrap();
+
+        if address < pcb.get_mem_start_address() || address >= pcb.get_mem_end_address() {
+            return Err(MemoryFault { process_id, addr: address });
+        }
+
+        Ok(())
+    }
+
     pub fn write_block_to(&mut self, address: usize, data: &[u32]) {
         let start_address = address;
         let end_address = address + data.len();
 
-        if end_address > MEMORY_SIZE {
+        if end_address > self.data.len() {
             panic!("Out of bounds memory access");
         }
 
@@ -62,9 +119,7 @@ impl Memory {
     }
 
     pub fn create_process(&mut self, program_info: &ProgramInfo, program_data: &[u32]) {
-        let start_address = self.current_data_idx;
-        let end_address = start_address + program_data.len();
-        self.current_data_idx = end_address;
+        let (start_address, end_address) = self.allocate(program_data.len());
 
         self.write_block_to(start_address, program_data);
 
@@ -73,6 +128,57 @@ impl Memory {
         self.pcb_map.insert(program_info.id, pcb);
     }
 
+    /// Reclaims the memory region held by the given process's PCB and
+    /// removes it from the process table, coalescing the freed block with
+    /// any adjacent free blocks.
+    pub fn free_process(&mut self, process_id: u32) {
+        let pcb = self.pcb_map.remove(&process_id)
+            .unwrap_or_else(|| panic!("No process found for id: {}", process_id));
+        let pcb = pcb.lock().unwrap();
+
+        self.deallocate(pcb.get_mem_start_address(), pcb.get_mem_end_address());
+    }
+
+    /// Finds the first free block large enough to hold `len` words,
+    /// splits off the remainder back into the free list, and returns the
+    /// allocated `[start, end)` range.
+    fn allocate(&mut self, len: usize) -> (usize, usize) {
+        let block_idx = self.free_list.iter()
+            .position(|&(_, block_len)| block_len >= len)
+            .unwrap_or_else(|| panic!("Not enough memory to allocate {} words", len));
+
+        let (start, block_len) = self.free_list[block_idx];
+        let end = start + len;
+
+        if block_len > len {
+            self.free_list[block_idx] = (end, block_len - len);
+        } else {
+            self.free_list.remove(block_idx);
+        }
+
+        (start, end)
+    }
+
+    /// Returns the `[start, end)` range to the free list, merging it with
+    /// any free blocks that are directly adjacent to it.
+    fn deallocate(&mut self, start: usize, end: usize) {
+        self.free_list.push((start, end - start));
+        self.free_list.sort_by_key(|&(block_start, _)| block_start);
+
+        let mut coalesced: Vec<(usize, usize)> = Vec::with_capacity(self.free_list.len());
+
+        for &(block_start, block_len) in self.free_list.iter() {
+            match coalesced.last_mut() {
+                Some((prev_start, prev_len)) if *prev_start + *prev_len == block_start => {
+                    *prev_len += block_len;
+                },
+                _ => coalesced.push((block_start, block_len)),
+            }
+        }
+
+        self.free_list = coalesced;
+    }
+
     pub fn get_pcb_for(&self, process_id: u32) -> Arc<Mutex<ProcessControlBlock>> {
         match self.pcb_map.get(&process_id) {
             Some(pcb) => pcb.clone(),
@@ -83,25 +189,109 @@ impl Memory {
     pub fn get_pcbs(&self, should_sort: bool) -> Vec<Arc<Mutex<ProcessControlBlock>>> {
         if should_sort {
             let mut pcbs = self.get_pcbs(false);
-            pcbs.sort_by(|a, b| a.lock().unwrap().get_id().cmp(&b.lock().unwrap().get_id()));
+            pcbs.sort_by_key(|pcb| pcb.lock().unwrap().get_id());
             pcbs
         } else {
             self.pcb_map.values().cloned().collect()
         }
     }
 
-    pub fn core_dump(&mut self) {
-        // TODO: Implement writing mem to file.
-        let disk = self.data;
-        //let disk = self.pcb_map;
-        self.pcb_map.clear();
-        let empty_data = [0; MEMORY_SIZE];
-        self.write_block_to(0, &empty_data);
-        self.current_data_idx = 0;
+    pub fn get_remaining_memory(&self) -> usize {
+        self.free_list.iter().map(|&(_, len)| len).sum()
+    }
+
+    /// Captures every resident process's full live state -- PC, registers,
+    /// `ProcessState`, memory region, and accumulated scheduling timers --
+    /// along with its instruction/data buffers, without clearing memory
+    /// the way `LongTermScheduler::unload_all` does. Used by `Driver::checkpoint` to suspend a
+    /// partially executed batch rather than archive a finished one.
+    pub fn snapshot(&self) -> Vec<checkpoint::ProcessSnapshot> {
+        self.pcb_map.values()
+            .map(|pcb| {
+                let pcb = pcb.lock().unwrap();
+
+                let program_info = ProgramInfo::new(
+                    pcb.get_id(),
+                    pcb.get_priority(),
+                    pcb.get_mem_in_start_address() - pcb.get_mem_start_address(),
+                    pcb.get_mem_out_start_address() - pcb.get_mem_in_start_address(),
+                    pcb.get_mem_temp_start_address() - pcb.get_mem_out_start_address(),
+                    pcb.get_mem_end_address() - pcb.get_mem_temp_start_address(),
+                );
+                let data = self.read_block_from(pcb.get_mem_start_address(), pcb.get_mem_end_address());
+
+                checkpoint::ProcessSnapshot {
+                    program_info,
+                    mem_start_address: pcb.get_mem_start_address(),
+                    mem_end_address: pcb.get_mem_end_address(),
+                    program_counter: pcb.program_counter,
+                    registers: pcb.registers,
+                    state_tag: pcb.state.as_tag(),
+                    turnaround_time_ms: pcb.get_turnaround_time_ms(),
+                    burst_times_ms: pcb.get_burst_times_ms().to_vec(),
+                    wait_time_ms: pcb.get_wait_time_ms(),
+                    io_wait_time_ms: pcb.get_io_wait_time_ms(),
+                    context_switch_count: pcb.get_context_switch_count(),
+                    io_request_count: pcb.get_io_request_count(),
+                    enqueued_at_ns: pcb.get_enqueued_at_ns(),
+                    instructions_executed_count: pcb.get_instructions_executed_count(),
+                    data,
+                }
+            })
+            .collect()
     }
 
-    pub fn get_remaining_memory(&self) -> usize {
-        MEMORY_SIZE - self.current_data_idx
+    /// Rebuilds resident processes from a `snapshot`, placing each one back
+    /// at its original memory region and restoring its PCB exactly as it
+    /// was. Assumes `self` is freshly constructed (empty `pcb_map`, full
+    /// `free_list`), as it is right after `Memory::new`.
+    pub fn restore_processes(&mut self, snapshots: Vec<checkpoint::ProcessSnapshot>) {
+        for snapshot in snapshots {
+            self.write_block_to(snapshot.mem_start_address, &snapshot.data);
+            self.reserve_range(snapshot.mem_start_address, snapshot.mem_end_address);
+
+            let mut pcb = ProcessControlBlock::new(&snapshot.program_info, snapshot.mem_start_address, snapshot.mem_end_address);
+            pcb.program_counter = snapshot.program_counter;
+            pcb.registers = snapshot.registers;
+            pcb.state = ProcessState::from_tag(snapshot.state_tag);
+            pcb.restore_timers(&snapshot);
+
+            // A terminated or faulted process already had its turnaround
+            // time ended before the checkpoint was taken; anything still
+            // live needs recording resumed so `end_record_turnaround_time`
+            // has a start point to measure from when it eventually
+            // finishes. The time spent suspended isn't counted against it.
+            if !matches!(pcb.state, ProcessState::Terminated | ProcessState::Faulted) {
+                pcb.start_record_turnaround_time();
+            }
+
+            self.pcb_map.insert(snapshot.program_info.id, Arc::new(Mutex::new(pcb)));
+        }
+    }
+
+    /// Carves `[start, end)` out of the free list, splitting or shrinking
+    /// whichever free block(s) it overlaps. Used by `restore_processes` to
+    /// mark a restored process's memory region as allocated again.
+    fn reserve_range(&mut self, start: usize, end: usize) {
+        let mut new_free_list = Vec::with_capacity(self.free_list.len() + 1);
+
+        for &(block_start, block_len) in &self.free_list {
+            let block_end = block_start + block_len;
+
+            if end <= block_start || start >= block_end {
+                new_free_list.push((block_start, block_len));
+                continue;
+            }
+
+            if block_start < start {
+                new_free_list.push((block_start, start - block_start));
+            }
+            if block_end > end {
+                new_free_list.push((end, block_end - end));
+            }
+        }
+
+        self.free_list = new_free_list;
     }
 }
 
@@ -204,8 +394,7 @@ mod tests {
     }
 
     #[test]
-    fn test_memory_core_dump() {
-        let mut disk = Disk::new();
+    fn test_memory_get_remaining_memory() {
         let mut memory = Memory::new();
         let program_info = ProgramInfo {
             id: 1,
@@ -216,16 +405,79 @@ mod tests {
             temp_buffer_size: 2,
             data_start_idx: 0
         };
-        disk.write_program(2,2,2,2,2,3, &[5,6,7,8,9]);
         let program_data = [1, 2, 3, 4, 5];
         memory.create_process(&program_info, &program_data);
-        memory.core_dump(&disk);
-        assert_eq!(memory.pcb_map.len(), 0);
-        assert_eq!(memory.read_from(0), 0);
+        assert_eq!(memory.get_remaining_memory(), 1019);
     }
 
     #[test]
-    fn test_memory_get_remaining_memory() {
+    fn test_memory_free_process_reclaims_and_coalesces() {
+        let mut memory = Memory::new();
+        let program_info_1 = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+        let program_info_2 = ProgramInfo {
+            id: 2,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+
+        memory.create_process(&program_info_1, &[1, 2, 3, 4, 5]);
+        memory.create_process(&program_info_2, &[6, 7, 8, 9, 10]);
+        assert_eq!(memory.get_remaining_memory(), 1014);
+
+        memory.free_process(1);
+        memory.free_process(2);
+
+        assert_eq!(memory.get_remaining_memory(), MEMORY_SIZE);
+        assert_eq!(memory.free_list.len(), 1);
+    }
+
+    #[test]
+    fn test_memory_create_process_reuses_freed_block() {
+        let mut memory = Memory::new();
+        let program_info_1 = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+        let program_info_2 = ProgramInfo {
+            id: 2,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+
+        memory.create_process(&program_info_1, &[1, 2, 3, 4, 5]);
+        memory.free_process(1);
+        memory.create_process(&program_info_2, &[6, 7, 8]);
+
+        let binding = memory.get_pcb_for(2);
+        let pcb = binding.lock().unwrap();
+        assert_eq!(pcb.get_mem_start_address(), 0);
+        assert_eq!(pcb.get_mem_end_address(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not enough memory")]
+    fn test_memory_create_process_out_of_memory() {
         let mut memory = Memory::new();
         let program_info = ProgramInfo {
             id: 1,
@@ -236,8 +488,102 @@ mod tests {
             temp_buffer_size: 2,
             data_start_idx: 0
         };
-        let program_data = [1, 2, 3, 4, 5];
-        memory.create_process(&program_info, &program_data);
-        assert_eq!(memory.get_remaining_memory(), 1019);
+
+        memory.create_process(&program_info, &vec![0; MEMORY_SIZE + 1]);
+    }
+
+    #[test]
+    fn test_memory_read_for_within_bounds() {
+        let mut memory = Memory::new();
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+
+        memory.create_process(&program_info, &[1, 2, 3, 4, 5]);
+
+        assert_eq!(memory.read_for(1, 2), Ok(3));
+    }
+
+    #[test]
+    fn test_memory_read_for_out_of_process_bounds_faults() {
+        let mut memory = Memory::new();
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+
+        memory.create_process(&program_info, &[1, 2, 3, 4, 5]);
+
+        assert_eq!(memory.read_for(1, 5), Err(MemoryFault { process_id: 1, addr: 5 }));
+    }
+
+    #[test]
+    fn test_memory_write_for_out_of_process_bounds_faults() {
+        let mut memory = Memory::new();
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0
+        };
+
+        memory.create_process(&program_info, &[1, 2, 3, 4, 5]);
+
+        let result = memory.write_for(1, 500, 99);
+
+        assert_eq!(result, Err(MemoryFault { process_id: 1, addr: 500 }));
+        assert_eq!(memory.read_from(500), 0);
+    }
+
+    #[test]
+    fn test_memory_snapshot_then_restore_processes() {
+        let mut memory = Memory::new();
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0,
+        };
+        memory.create_process(&program_info, &[1, 2, 3, 4, 5]);
+
+        {
+            let pcb = memory.get_pcb_for(1);
+            let mut pcb = pcb.lock().unwrap();
+            pcb.program_counter = 3;
+            pcb.registers[0] = 42;
+        }
+
+        let snapshot = memory.snapshot();
+
+        let mut restored = Memory::new();
+        restored.restore_processes(snapshot);
+
+        let pcb = restored.get_pcb_for(1);
+        let pcb = pcb.lock().unwrap();
+        assert_eq!(pcb.program_counter, 3);
+        assert_eq!(pcb.registers[0], 42);
+        assert_eq!(pcb.get_mem_start_address(), 0);
+        assert_eq!(pcb.get_mem_end_address(), 5);
+        drop(pcb);
+
+        assert_eq!(restored.read_block_from(0, 5), vec![1, 2, 3, 4, 5]);
+        assert_eq!(restored.get_remaining_memory(), MEMORY_SIZE - 5);
     }
 }
\ No newline at end of file
@@ -1,13 +1,20 @@
 mod cpu;
+mod disassemble;
+mod interrupt_controller;
 mod long_term_scheduler;
 pub mod memory;
 mod process_control_block;
+mod scheduler;
+mod scheduler_stats;
 mod short_term_scheduler;
+mod syscall;
 
 use cpu::Cpu;
+use interrupt_controller::{InterruptController, IO_COMPLETE_IRQ};
 use long_term_scheduler::LongTermScheduler;
-use memory::Memory;
-use process_control_block::{ProcessControlBlock, ProcessState};
+use memory::{Memory, MemoryFault};
+use process_control_block::{Flags, ProcessControlBlock, ProcessState};
+use scheduler_stats::SchedulerStats;
 use short_term_scheduler::{StsSchedulingAlg, ShortTermScheduler};
 
 pub mod driver;
@@ -2,6 +2,7 @@ use core::panic;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::io::ProgramInfo;
+use crate::io::checkpoint::ProcessSnapshot;
 
 #[derive(Clone, Copy)]
 pub(crate) enum ProcessState {
@@ -9,11 +10,88 @@ pub(crate) enum ProcessState {
     Running,
     Waiting,
     Terminated,
+    /// Raised in place of `Terminated` when the process's own instruction
+    /// stream trapped (see `cpu::TrapCause`) rather than executing `HLT`.
+    Faulted,
+    /// Raised by a `TRAP`/`SWI` instruction to request a kernel service
+    /// (see `cpu::PendingSyscall`); the process stays resident and is
+    /// expected to resume, not be torn down like `Terminated`/`Faulted`.
+    SystemCall,
+    /// Raised when a process is cut off mid-burst by `Cpu::execute_process`'s
+    /// `quantum` expiring, rather than by `HLT`, a fault, or an I/O request
+    /// of its own. Distinct from `Ready` so the scheduler can tell a
+    /// round-robin time slice apart from the other ways a process lands
+    /// back in the ready queue.
+    Preempted,
+    /// Raised by `cycle` when the upcoming instruction's address is in
+    /// `CpuResources::breakpoints`, just before that instruction would
+    /// otherwise execute; or right after an instruction that touched an
+    /// address in `CpuResources::watchpoints`, or the one instruction
+    /// single-stepped via `Cpu::step`, has run. Lets a
+    /// debugging front-end pause a process mid-burst without tearing it
+    /// down or handing the CPU to another process, unlike every other
+    /// interrupt type.
+    Breakpoint,
+}
+
+impl ProcessState {
+    /// Plain numeric encoding used by `io::checkpoint`, which doesn't
+    /// depend on `kernel` types.
+    pub(crate) fn as_tag(&self) -> u8 {
+        match self {
+            ProcessState::Ready => 0,
+            ProcessState::Running => 1,
+            ProcessState::Waiting => 2,
+            ProcessState::Terminated => 3,
+            ProcessState::Faulted => 4,
+            ProcessState::SystemCall => 5,
+            ProcessState::Preempted => 6,
+            ProcessState::Breakpoint => 7,
+        }
+    }
+
+    pub(crate) fn from_tag(tag: u8) -> ProcessState {
+        match tag {
+            0 => ProcessState::Ready,
+            1 => ProcessState::Running,
+            2 => ProcessState::Waiting,
+            3 => ProcessState::Terminated,
+            4 => ProcessState::Faulted,
+            5 => ProcessState::SystemCall,
+            6 => ProcessState::Preempted,
+            7 => ProcessState::Breakpoint,
+            _ => panic!("Unknown process state tag: {}", tag),
+        }
+    }
+}
+
+/// Condition codes set by the CPU's arithmetic instructions (see
+/// `Cpu::compute_flags`) and tested by the signed conditional branches
+/// (`BGZ`, `BLZ`, `BEZ`, `BNZ`) instead of comparing `u32` registers as if
+/// they were signed, which can never be true for `Negative`/`Carry`-style
+/// conditions. Saved and restored alongside `registers` in
+/// `Cpu::execute_process` so a preempted process's flags aren't clobbered
+/// by whichever process runs next.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Flags {
+    pub zero: bool,
+    pub negative: bool,
+    /// Set by `Cpu::compute_flags` from `overflowing_add`/`overflowing_sub`/
+    /// `overflowing_mul`'s unsigned carry-out and signed-overflow results,
+    /// the same way `zero`/`negative` are. No branch opcode reads these
+    /// back yet -- `BGZ`/`BLZ`/`BEZ`/`BNZ` only need `zero`/`negative` --
+    /// but they're computed alongside the flags that are, ready for a
+    /// carry/overflow-sensitive branch (`BC`, `BO`) to consume later.
+    #[allow(dead_code)]
+    pub carry: bool,
+    #[allow(dead_code)]
+    pub overflow: bool,
 }
 
 pub(crate) struct ProcessControlBlock {
     pub program_counter: usize,
     pub registers: [u32; 16],
+    pub flags: Flags,
     pub state: ProcessState,
     
     id: u32,
@@ -32,6 +110,20 @@ pub(crate) struct ProcessControlBlock {
     burst_times_ms: Vec<f64>,
     current_burst_start_time_ns: u128,
     burst_time_is_recording: bool,
+
+    wait_time_ms: f64,
+    wait_start_time_ns: u128,
+    wait_time_is_recording: bool,
+
+    io_wait_time_ms: f64,
+    io_wait_start_time_ns: u128,
+    io_wait_time_is_recording: bool,
+
+    context_switch_count: u32,
+    io_request_count: u32,
+    instructions_executed_count: u64,
+
+    enqueued_at_ns: u128,
 }
 
 impl ProcessControlBlock {
@@ -39,6 +131,7 @@ impl ProcessControlBlock {
         ProcessControlBlock {
             program_counter: 0,
             registers: [0; 16],
+            flags: Flags::default(),
             state: ProcessState::Ready,
 
             id: program_info.id,
@@ -57,6 +150,20 @@ impl ProcessControlBlock {
             burst_times_ms: Vec::new(),
             current_burst_start_time_ns: 0,
             burst_time_is_recording: false,
+
+            wait_time_ms: 0.0,
+            wait_start_time_ns: 0,
+            wait_time_is_recording: false,
+
+            io_wait_time_ms: 0.0,
+            io_wait_start_time_ns: 0,
+            io_wait_time_is_recording: false,
+
+            context_switch_count: 0,
+            io_request_count: 0,
+            instructions_executed_count: 0,
+
+            enqueued_at_ns: 0,
         }
     }
 
@@ -94,7 +201,7 @@ impl ProcessControlBlock {
     }
 
     pub fn end_record_turnaround_time(&mut self) {
-        if self.turnaround_time_is_recording == false {
+        if !self.turnaround_time_is_recording {
             panic!("Process time is not being recorded.");
         }
 
@@ -116,7 +223,7 @@ impl ProcessControlBlock {
     }
 
     pub fn end_record_burst_time(&mut self) {
-        if self.burst_time_is_recording == false {
+        if !self.burst_time_is_recording {
             panic!("Burst time is not being recorded.");
         }
 
@@ -128,12 +235,105 @@ impl ProcessControlBlock {
         self.burst_time_is_recording = false;
     }
 
-    pub fn get_avg_burst_time_ms(&self) -> f64 {
-        if self.burst_times_ms.is_empty() {
-            return 0.0;
+    pub fn get_total_cpu_time_ms(&self) -> f64 {
+        self.burst_times_ms.iter().sum()
+    }
+
+    pub fn get_burst_times_ms(&self) -> &[f64] {
+        &self.burst_times_ms
+    }
+
+    pub fn start_record_wait_time(&mut self) {
+        self.wait_start_time_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        self.wait_time_is_recording = true;
+    }
+
+    pub fn end_record_wait_time(&mut self) {
+        if !self.wait_time_is_recording {
+            panic!("Wait time is not being recorded.");
         }
 
-        let total_burst_time_ms: f64 = self.burst_times_ms.iter().sum();
-        total_burst_time_ms / self.burst_times_ms.len() as f64
+        let wait_end_time_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let wait_time_ns = wait_end_time_ns - self.wait_start_time_ns;
+
+        self.wait_time_ms += wait_time_ns as f64 / 1_000_000.0;
+        self.wait_time_is_recording = false;
+    }
+
+    pub fn get_wait_time_ms(&self) -> f64 {
+        self.wait_time_ms
+    }
+
+    pub fn start_record_io_wait_time(&mut self) {
+        self.io_wait_start_time_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        self.io_wait_time_is_recording = true;
+    }
+
+    pub fn end_record_io_wait_time(&mut self) {
+        if !self.io_wait_time_is_recording {
+            panic!("I/O wait time is not being recorded.");
+        }
+
+        let io_wait_end_time_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let io_wait_time_ns = io_wait_end_time_ns - self.io_wait_start_time_ns;
+
+        self.io_wait_time_ms += io_wait_time_ns as f64 / 1_000_000.0;
+        self.io_wait_time_is_recording = false;
+    }
+
+    pub fn get_io_wait_time_ms(&self) -> f64 {
+        self.io_wait_time_ms
+    }
+
+    pub fn record_context_switch(&mut self) {
+        self.context_switch_count += 1;
+    }
+
+    pub fn get_context_switch_count(&self) -> u32 {
+        self.context_switch_count
+    }
+
+    pub fn record_io_request(&mut self) {
+        self.io_request_count += 1;
+    }
+
+    pub fn get_io_request_count(&self) -> u32 {
+        self.io_request_count
+    }
+
+    /// Accumulates however many instructions `Cpu::take_instructions_executed_in_quantum`
+    /// reports this process ran in its most recent burst, so the scheduler
+    /// can report per-process CPU time in instructions alongside the
+    /// wall-clock burst times (see `get_total_cpu_time_ms`).
+    pub fn record_instructions_executed(&mut self, count: usize) {
+        self.instructions_executed_count += count as u64;
+    }
+
+    pub fn get_instructions_executed_count(&self) -> u64 {
+        self.instructions_executed_count
+    }
+
+    /// Stamps the moment this process entered the ready queue, so a
+    /// priority scheduler can age it: see `PriorityProcessControlBlock`.
+    pub fn mark_enqueued(&mut self) {
+        self.enqueued_at_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    }
+
+    pub fn get_enqueued_at_ns(&self) -> u128 {
+        self.enqueued_at_ns
+    }
+
+    /// Rehydrates every accumulated timer/counter on a freshly constructed
+    /// PCB from a checkpoint (see `Memory::restore_processes`), without
+    /// re-entering a "currently recording" state for any of them.
+    pub fn restore_timers(&mut self, snapshot: &ProcessSnapshot) {
+        self.turnaround_time_ms = snapshot.turnaround_time_ms;
+        self.burst_times_ms = snapshot.burst_times_ms.clone();
+        self.wait_time_ms = snapshot.wait_time_ms;
+        self.io_wait_time_ms = snapshot.io_wait_time_ms;
+        self.context_switch_count = snapshot.context_switch_count;
+        self.io_request_count = snapshot.io_request_count;
+        self.enqueued_at_ns = snapshot.enqueued_at_ns;
+        self.instructions_executed_count = snapshot.instructions_executed_count;
     }
 }
\ No newline at end of file
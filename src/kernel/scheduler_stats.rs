@@ -0,0 +1,246 @@
+use std::sync::{Arc, Mutex};
+
+use super::ProcessControlBlock;
+
+const NUM_BURST_HISTOGRAM_BUCKETS: usize = 5;
+
+/// Aggregate scheduling metrics computed across a batch of finished
+/// processes, built from the per-process timers already tracked on each
+/// `ProcessControlBlock`.
+pub(crate) struct SchedulerStats {
+    pub num_processes: usize,
+    pub avg_turnaround_time_ms: f64,
+    pub median_turnaround_time_ms: f64,
+    pub max_turnaround_time_ms: f64,
+    pub avg_wait_time_ms: f64,
+    pub avg_io_wait_time_ms: f64,
+    pub total_context_switches: u32,
+    pub total_io_requests: u32,
+    pub cpu_utilization: f64,
+    /// Processes completed per second, approximated as `num_processes`
+    /// divided by the longest turnaround in the batch, since the first
+    /// process in a batch starts at (or very near) the batch's start time.
+    pub throughput_per_sec: f64,
+    pub burst_time_histogram: Vec<BurstHistogramBucket>,
+}
+
+/// One bucket of a fixed-width histogram over every recorded burst time in
+/// the batch, bucketed from `0` up to the longest burst observed.
+pub(crate) struct BurstHistogramBucket {
+    pub upper_bound_ms: f64,
+    pub count: u32,
+}
+
+impl SchedulerStats {
+    /// Collects aggregate statistics from a batch of process control blocks
+    /// after they have all finished running.
+    pub fn collect(pcbs: &[Arc<Mutex<ProcessControlBlock>>]) -> SchedulerStats {
+        let num_processes = pcbs.len();
+
+        if num_processes == 0 {
+            return SchedulerStats {
+                num_processes: 0,
+                avg_turnaround_time_ms: 0.0,
+                median_turnaround_time_ms: 0.0,
+                max_turnaround_time_ms: 0.0,
+                avg_wait_time_ms: 0.0,
+                avg_io_wait_time_ms: 0.0,
+                total_context_switches: 0,
+                total_io_requests: 0,
+                cpu_utilization: 0.0,
+                throughput_per_sec: 0.0,
+                burst_time_histogram: Vec::new(),
+            };
+        }
+
+        let mut total_turnaround_time_ms = 0.0;
+        let mut total_wait_time_ms = 0.0;
+        let mut total_io_wait_time_ms = 0.0;
+        let mut total_cpu_time_ms = 0.0;
+        let mut total_context_switches = 0;
+        let mut total_io_requests = 0;
+        let mut turnaround_times_ms = Vec::with_capacity(num_processes);
+        let mut burst_times_ms = Vec::new();
+
+        for pcb in pcbs {
+            let pcb = pcb.lock().unwrap();
+
+            let turnaround_time_ms = pcb.get_turnaround_time_ms();
+
+            total_turnaround_time_ms += turnaround_time_ms;
+            total_wait_time_ms += pcb.get_wait_time_ms();
+            total_io_wait_time_ms += pcb.get_io_wait_time_ms();
+            total_cpu_time_ms += pcb.get_total_cpu_time_ms();
+            total_context_switches += pcb.get_context_switch_count();
+            total_io_requests += pcb.get_io_request_count();
+            turnaround_times_ms.push(turnaround_time_ms);
+            burst_times_ms.extend_from_slice(pcb.get_burst_times_ms());
+        }
+
+        let num_processes_f64 = num_processes as f64;
+
+        turnaround_times_ms.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let median_turnaround_time_ms = if turnaround_times_ms.len() % 2 == 0 {
+            let mid = turnaround_times_ms.len() / 2;
+            (turnaround_times_ms[mid - 1] + turnaround_times_ms[mid]) / 2.0
+        } else {
+            turnaround_times_ms[turnaround_times_ms.len() / 2]
+        };
+        let max_turnaround_time_ms = *turnaround_times_ms.last().unwrap();
+
+        SchedulerStats {
+            num_processes,
+            avg_turnaround_time_ms: total_turnaround_time_ms / num_processes_f64,
+            median_turnaround_time_ms,
+            max_turnaround_time_ms,
+            avg_wait_time_ms: total_wait_time_ms / num_processes_f64,
+            avg_io_wait_time_ms: total_io_wait_time_ms / num_processes_f64,
+            total_context_switches,
+            total_io_requests,
+            cpu_utilization: if total_turnaround_time_ms > 0.0 {
+                (total_cpu_time_ms / total_turnaround_time_ms).min(1.0)
+            } else {
+                0.0
+            },
+            throughput_per_sec: if max_turnaround_time_ms > 0.0 {
+                num_processes_f64 / (max_turnaround_time_ms / 1000.0)
+            } else {
+                0.0
+            },
+            burst_time_histogram: SchedulerStats::build_burst_histogram(&burst_times_ms),
+        }
+    }
+
+    fn build_burst_histogram(burst_times_ms: &[f64]) -> Vec<BurstHistogramBucket> {
+        let max_burst_time_ms = burst_times_ms.iter().cloned().fold(0.0, f64::max);
+
+        if max_burst_time_ms <= 0.0 {
+            return Vec::new();
+        }
+
+        let bucket_width_ms = max_burst_time_ms / NUM_BURST_HISTOGRAM_BUCKETS as f64;
+        let mut buckets: Vec<BurstHistogramBucket> = (1..=NUM_BURST_HISTOGRAM_BUCKETS)
+            .map(|i| BurstHistogramBucket { upper_bound_ms: bucket_width_ms * i as f64, count: 0 })
+            .collect();
+
+        for burst_time_ms in burst_times_ms {
+            let bucket_idx = ((burst_time_ms / bucket_width_ms).ceil() as usize)
+                .saturating_sub(1)
+                .min(NUM_BURST_HISTOGRAM_BUCKETS - 1);
+            buckets[bucket_idx].count += 1;
+        }
+
+        buckets
+    }
+
+    /// Renders a human-readable report table, labeled with the scheduling
+    /// algorithm that produced it so successive runs under different
+    /// `StsSchedulingAlg` variants can be appended to the same file and
+    /// compared side by side.
+    pub fn to_report(&self, scheduling_alg_label: &str) -> String {
+        let mut report = String::new();
+
+        report.push_str(&format!("=== Scheduler stats ({}) ===\n", scheduling_alg_label));
+        report.push_str(&format!("Processes completed:     {}\n", self.num_processes));
+        report.push_str(&format!("Throughput:              {:.3} processes/sec\n", self.throughput_per_sec));
+        report.push_str(&format!(
+            "Turnaround time (ms):    avg {:.3}, median {:.3}, max {:.3}\n",
+            self.avg_turnaround_time_ms, self.median_turnaround_time_ms, self.max_turnaround_time_ms,
+        ));
+        report.push_str(&format!("Wait time (avg ms):      {:.3}\n", self.avg_wait_time_ms));
+        report.push_str(&format!("I/O wait time (avg ms):  {:.3}\n", self.avg_io_wait_time_ms));
+        report.push_str(&format!("Context switches:        {}\n", self.total_context_switches));
+        report.push_str(&format!("I/O requests:            {}\n", self.total_io_requests));
+        report.push_str(&format!("CPU utilization:         {:.1}%\n", self.cpu_utilization * 100.0));
+
+        if self.burst_time_histogram.is_empty() {
+            report.push_str("Burst time histogram:    (no burst data)\n");
+        } else {
+            report.push_str("Burst time histogram (upper bound ms -> count):\n");
+
+            for bucket in &self.burst_time_histogram {
+                report.push_str(&format!("  <= {:>8.3}: {}\n", bucket.upper_bound_ms, bucket.count));
+            }
+        }
+
+        report.push('\n');
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::ProgramInfo;
+
+    #[test]
+    fn test_scheduler_stats_collect_empty() {
+        let stats = SchedulerStats::collect(&[]);
+
+        assert_eq!(stats.num_processes, 0);
+        assert_eq!(stats.avg_turnaround_time_ms, 0.0);
+        assert_eq!(stats.cpu_utilization, 0.0);
+    }
+
+    #[test]
+    fn test_scheduler_stats_collect() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0,
+        };
+
+        let pcb = Arc::new(Mutex::new(ProcessControlBlock::new(&program_info, 0, 5)));
+        {
+            let mut pcb = pcb.lock().unwrap();
+
+            pcb.record_context_switch();
+            pcb.record_context_switch();
+            pcb.record_io_request();
+        }
+
+        let stats = SchedulerStats::collect(&[pcb]);
+
+        assert_eq!(stats.num_processes, 1);
+        assert_eq!(stats.total_context_switches, 2);
+        assert_eq!(stats.total_io_requests, 1);
+    }
+
+    #[test]
+    fn test_scheduler_stats_collect_computes_median_max_and_histogram() {
+        let program_info = ProgramInfo {
+            id: 1,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 2,
+            data_start_idx: 0,
+        };
+
+        let pcb = Arc::new(Mutex::new(ProcessControlBlock::new(&program_info, 0, 5)));
+        {
+            let mut pcb = pcb.lock().unwrap();
+
+            pcb.start_record_turnaround_time();
+            pcb.end_record_turnaround_time();
+
+            pcb.start_record_burst_time();
+            pcb.end_record_burst_time();
+        }
+
+        let turnaround_time_ms = pcb.lock().unwrap().get_turnaround_time_ms();
+        let stats = SchedulerStats::collect(&[pcb]);
+
+        assert_eq!(stats.median_turnaround_time_ms, turnaround_time_ms);
+        assert_eq!(stats.max_turnaround_time_ms, turnaround_time_ms);
+
+        let total_histogram_count: u32 = stats.burst_time_histogram.iter().map(|b| b.count).sum();
+        assert_eq!(total_histogram_count, 1);
+    }
+}
@@ -1,50 +1,97 @@
 use std::collections::{BinaryHeap, VecDeque};
-use std::sync::{Arc, Condvar, Mutex, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, Condvar, Mutex, RwLock, atomic::{AtomicBool, Ordering}};
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::{Cpu, ProcessControlBlock, ProcessState};
+use super::{Cpu, InterruptController, Memory, ProcessControlBlock, ProcessState, IO_COMPLETE_IRQ};
+use super::syscall::{self, Exception, SyscallOutcome};
 
 #[allow(dead_code)]
 pub(crate) enum StsSchedulingAlg {
     Fifo,
-    Priority,
+    /// `aging_interval_ms` is how long, in milliseconds, a process must
+    /// wait in the ready queue to gain one point of effective priority
+    /// (see `PriorityProcessControlBlock`). `0` disables aging, leaving
+    /// ordering purely by static priority.
+    Priority { aging_interval_ms: u128 },
+    RoundRobin { quantum: usize },
 }
 
+/// Dispatches ready processes onto however many CPU cores it was given, one
+/// dispatch thread per core all pulling from the same shared ready queue
+/// (an M:N mapping of processes to cores, in the spirit of a green-thread
+/// scheduler) rather than one thread tied to a single CPU.
 pub(crate) struct ShortTermScheduler {
     resources: Arc<Mutex<ShortTermSchedulerResources>>,
     dispatch_should_terminate: Arc<AtomicBool>,
+    alg_label: &'static str,
 }
 
 impl ShortTermScheduler {
-    pub fn new(cpu: Arc<Mutex<Cpu>>, scheduling_alg: StsSchedulingAlg) -> ShortTermScheduler {
-        let ready_queue: Box<dyn SchedulerQueue + Send> = match scheduling_alg {
-            StsSchedulingAlg::Fifo => Box::new(FifoQueue::new()),
-            StsSchedulingAlg::Priority => Box::new(PriorityQueue::new()),
+    pub fn new(cpus: Vec<Arc<Mutex<Cpu>>>, memory: Arc<RwLock<Memory>>, scheduling_alg: StsSchedulingAlg) -> ShortTermScheduler {
+        let num_cores = cpus.len();
+        let (ready_queue, quantum, alg_label): (Box<dyn SchedulerQueue + Send>, Option<usize>, &'static str) = match scheduling_alg {
+            StsSchedulingAlg::Fifo => (Box::new(FifoQueue::new()), None, "FIFO"),
+            StsSchedulingAlg::Priority { aging_interval_ms } => (Box::new(PriorityQueue::new(aging_interval_ms * 1_000_000)), None, "Priority"),
+            StsSchedulingAlg::RoundRobin { quantum } => (Box::new(FifoQueue::new()), Some(quantum), "RoundRobin"),
         };
 
         let resources = Arc::new(Mutex::new(ShortTermSchedulerResources::new(
-            cpu,
+            cpus,
+            memory,
             ready_queue,
+            quantum,
         )));
         let dispatch_should_terminate = Arc::new(AtomicBool::new(false));
 
-        let resources_clone = resources.clone();
-        let dispatch_should_terminate_clone = dispatch_should_terminate.clone();
+        for core_id in 0..num_cores {
+            let resources_clone = resources.clone();
+            let dispatch_should_terminate_clone = dispatch_should_terminate.clone();
 
-        thread::spawn(move || {
-            while !dispatch_should_terminate_clone.load(Ordering::Relaxed) {
-                ShortTermScheduler::dispatch(&resources_clone);
-            }
-        });
+            thread::spawn(move || {
+                while !dispatch_should_terminate_clone.load(Ordering::Relaxed) {
+                    ShortTermScheduler::dispatch(&resources_clone, core_id);
+                }
+            });
+        }
 
         ShortTermScheduler {
             resources,
             dispatch_should_terminate,
+            alg_label,
         }
     }
 
+    /// Name of the scheduling algorithm this instance was constructed
+    /// with, used to label its batch in the scheduler stats report so
+    /// runs under different `StsSchedulingAlg` variants can be compared.
+    pub fn alg_label(&self) -> &'static str {
+        self.alg_label
+    }
+
+    /// Ids of every process currently sitting in the ready queue and in
+    /// the waiting (I/O) queue, in that order. Used by `Driver::checkpoint`
+    /// to persist which processes still need to run without disturbing the
+    /// queues themselves.
+    pub fn snapshot_queue_ids(&self) -> (Vec<u32>, Vec<u32>) {
+        let resources = self.resources.lock().unwrap();
+
+        let ready_ids = resources.ready_queue.ids();
+        let waiting_ids = resources.waiting_queue.iter()
+            .map(|pcb| pcb.lock().unwrap().get_id())
+            .collect();
+
+        (ready_ids, waiting_ids)
+    }
+
     pub fn schedule_process(&mut self, pcb: Arc<Mutex<ProcessControlBlock>>) {
         let mut resources = self.resources.lock().unwrap();
+
+        {
+            let mut pcb = pcb.lock().unwrap();
+            pcb.start_record_wait_time();
+            pcb.mark_enqueued();
+        }
         resources.ready_queue.push(pcb);
 
         let (lock, condvar) = &*resources.all_procs_are_finished_condvar;
@@ -68,8 +115,12 @@ impl ShortTermScheduler {
         }
     }
 
-    fn dispatch(resources: &Arc<Mutex<ShortTermSchedulerResources>>) {
-        // Sleep until new process is added to the ready queue.
+    /// Runs one iteration of a single core's dispatch loop. `core_id` is
+    /// this core's index into `resources.cpus`/`resources.current_pcbs`;
+    /// every core runs this same function, independently, against the one
+    /// shared ready queue.
+    fn dispatch(resources: &Arc<Mutex<ShortTermSchedulerResources>>, core_id: usize) {
+        // Sleep until a new process is added to the ready queue.
         let all_procs_are_finished_condvar = {
             let resources = resources.lock().unwrap();
             resources.all_procs_are_finished_condvar.clone()
@@ -85,44 +136,230 @@ impl ShortTermScheduler {
         }
 
         // Dispatch process.
-        let (cpu, in_pcb, out_pcb) = {
+        let (cpu, in_pcb, out_pcb, quantum) = {
             let mut resources = resources.lock().unwrap();
 
-            let cpu = resources.cpu.clone();
-            let in_pcb = resources.ready_queue.pop().unwrap();
-            let out_pcb = resources.current_pcb.clone();
-            resources.current_pcb = Some(in_pcb.clone());
+            let cpu = resources.cpus[core_id].clone();
+            let in_pcb = resources.ready_queue.pop();
+            let out_pcb = resources.current_pcbs[core_id].clone();
+            let quantum = resources.quantum;
 
-            (cpu, in_pcb, out_pcb)
+            // If nothing new was queued for this core, it's still busy
+            // with `out_pcb` for the entire blocking `await_process_interrupt`
+            // window below -- leave the slot showing that instead of
+            // clearing it to `None`, or another core's "all finished" check
+            // (further down) could see this core as idle while its process
+            // is still genuinely running.
+            resources.current_pcbs[core_id] = in_pcb.clone().or_else(|| out_pcb.clone());
+
+            (cpu, in_pcb, out_pcb, quantum)
         };
-        
+
+        if in_pcb.is_none() && out_pcb.is_none() {
+            // Nothing was running on this core and nothing is queued for
+            // it yet. A process this core parked on I/O reports completion
+            // only through this core's own `Cpu`, so drain that here before
+            // looping back around -- no other core's `handle_interrupts`
+            // call ever sees it.
+            ShortTermScheduler::handle_interrupts(&mut resources.lock().unwrap(), &cpu);
+            return;
+        }
+
+        if let Some(in_pcb) = &in_pcb {
+            let mut in_pcb = in_pcb.lock().unwrap();
+            in_pcb.end_record_wait_time();
+            in_pcb.record_context_switch();
+        }
+
         let out_pcb_clone = out_pcb.clone();
-        let out_pcb_state;
+        let in_pcb_clone = in_pcb.clone();
+
+        // `out_pcb` is the process that was previously running on this
+        // specific core (if any); `in_pcb` is whatever this core just
+        // picked up off the shared ready queue (if anything was waiting).
+        // One of the two is always Some, since `Cpu::execute_process`
+        // requires a previous process to write back, a new one to start,
+        // or both.
+        let out_pcb_state = if let Some(out_pcb_ref) = &out_pcb {
+            let mut cpu_guard = cpu.lock().unwrap();
+            let out_pcb_state = cpu_guard.await_process_interrupt(); // Blocks until this core's current process is done.
+            let trap_cause = cpu_guard.take_trap_cause();
+            let pending_syscall = cpu_guard.take_pending_syscall();
+            let instructions_executed = cpu_guard.take_instructions_executed_in_quantum();
+
+            // Any syscall other than `Exception::Exit` resumes the same process
+            // straight off the CPU's still-loaded state via `resume`/
+            // `resume_after_syscall`, as long as nothing else is queued to
+            // take this core -- skipping `execute_process`'s write-back
+            // and the full cache reload this same process would otherwise
+            // pay for coming back off the ready queue for no real reason.
+            // `Exception::Exit` falls through to the ordinary path below so the
+            // `SystemCall` arm can tear the process down the same way
+            // every other terminal state does.
+            if in_pcb.is_none() {
+                if let Some(pending) = &pending_syscall {
+                    if matches!(out_pcb_state, ProcessState::SystemCall) {
+                        let exception = Exception::from_pending_syscall(pending);
+
+                        if !matches!(exception, Exception::Exit) {
+                            let dest_reg = match &exception {
+                                Exception::ReadInput { dest_reg, .. } => Some(*dest_reg),
+                                _ => None,
+                            };
+
+                            let out_pcb = out_pcb_ref.clone();
+                            let mut sts_resources = resources.lock().unwrap();
+
+                            {
+                                let mut pcb = out_pcb.lock().unwrap();
+                                syscall::handle_syscall(&mut pcb, &mut sts_resources.memory.write().unwrap(), exception);
+                            }
+
+                            match dest_reg {
+                                Some(reg) => {
+                                    let value = out_pcb.lock().unwrap().registers[reg];
+                                    cpu_guard.resume_after_syscall(reg, value);
+                                },
+                                None => cpu_guard.resume(),
+                            }
+
+                            out_pcb.lock().unwrap().record_instructions_executed(instructions_executed);
+                            ShortTermScheduler::handle_interrupts(&mut sts_resources, &cpu);
+
+                            return;
+                        }
+                    }
+                }
+            }
+
+            if let Some(in_pcb) = &in_pcb {
+                in_pcb.lock().unwrap().state = ProcessState::Running;
+            }
+
+            cpu_guard.execute_process(in_pcb, out_pcb, quantum);
 
-        let mut cpu = cpu.lock().unwrap();
-        out_pcb_state = cpu.await_process_interrupt(); // Blocks until current process is done.
+            Some((out_pcb_state, trap_cause, pending_syscall, instructions_executed))
+        } else {
+            // First process ever dispatched to this core: there's nothing
+            // previously running on it to await or write back.
+            let mut cpu_guard = cpu.lock().unwrap();
 
-        in_pcb.lock().unwrap().state = ProcessState::Running;
-        cpu.execute_process(in_pcb, out_pcb);
+            in_pcb.as_ref().unwrap().lock().unwrap().state = ProcessState::Running;
+            cpu_guard.execute_process(in_pcb, None, quantum);
+
+            None
+        };
 
         let mut resources = resources.lock().unwrap();
-        match out_pcb_state {
-            ProcessState::Ready => {
-                out_pcb_clone.as_ref().unwrap().lock().unwrap().state = ProcessState::Ready;
-                resources.ready_queue.push(out_pcb_clone.unwrap());
-            },
-            ProcessState::Waiting => {
-                out_pcb_clone.as_ref().unwrap().lock().unwrap().state = ProcessState::Waiting;
-                // Unimplemented due to lack of I/O devices and therefore DMA channel.
-            },
-            ProcessState::Terminated => { /* Do nothing. */ },
-            ProcessState::Running => {
-                panic!("Process should not be set to running after being moved out of the CPU.");
-            },
+
+        // Now that `execute_process` has actually started whatever this
+        // core is running next, make the slot reflect that: `in_pcb_clone`
+        // if something was dispatched, or `None` if the core just went
+        // idle with nothing queued for it.
+        resources.current_pcbs[core_id] = in_pcb_clone;
+
+        if let Some((out_pcb_state, trap_cause, pending_syscall, instructions_executed)) = out_pcb_state {
+            out_pcb_clone.as_ref().unwrap().lock().unwrap().record_instructions_executed(instructions_executed);
+
+            match out_pcb_state {
+                ProcessState::Ready => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let mut pcb = out_pcb.lock().unwrap();
+
+                    pcb.state = ProcessState::Ready;
+                    pcb.start_record_wait_time();
+                    pcb.mark_enqueued();
+                    drop(pcb);
+
+                    resources.ready_queue.push(out_pcb);
+                },
+                ProcessState::Preempted => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let mut pcb = out_pcb.lock().unwrap();
+
+                    pcb.state = ProcessState::Preempted;
+                    pcb.start_record_wait_time();
+                    pcb.mark_enqueued();
+                    drop(pcb);
+
+                    resources.ready_queue.push(out_pcb);
+                },
+                ProcessState::Waiting => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let mut pcb = out_pcb.lock().unwrap();
+
+                    pcb.state = ProcessState::Waiting;
+                    pcb.record_io_request();
+                    pcb.start_record_io_wait_time();
+                    drop(pcb);
+
+                    // The DMA channel services this process's RD/WR
+                    // asynchronously and reports completion through
+                    // `Cpu::take_completed_io` (see `handle_interrupts`), not
+                    // through an immediate IRQ here.
+                    resources.waiting_queue.push_back(out_pcb);
+                },
+                ProcessState::Terminated => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let mut pcb = out_pcb.lock().unwrap();
+
+                    pcb.state = ProcessState::Terminated;
+                    pcb.end_record_turnaround_time();
+                },
+                ProcessState::Faulted => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let mut pcb = out_pcb.lock().unwrap();
+                    let process_id = pcb.get_id();
+
+                    pcb.state = ProcessState::Faulted;
+                    pcb.end_record_turnaround_time();
+                    drop(pcb);
+
+                    eprintln!("Process {} faulted: {:?}", process_id, trap_cause.expect("Faulted interrupt without a recorded trap cause"));
+                },
+                ProcessState::SystemCall => {
+                    let out_pcb = out_pcb_clone.unwrap();
+                    let pending_syscall = pending_syscall.expect("SystemCall interrupt without a recorded syscall");
+                    let exception = Exception::from_pending_syscall(&pending_syscall);
+                    let mut pcb = out_pcb.lock().unwrap();
+
+                    let outcome = syscall::handle_syscall(&mut pcb, &mut resources.memory.write().unwrap(), exception);
+
+                    match outcome {
+                        SyscallOutcome::Continue => {
+                            pcb.state = ProcessState::Ready;
+                            pcb.start_record_wait_time();
+                            pcb.mark_enqueued();
+                            drop(pcb);
+
+                            resources.ready_queue.push(out_pcb);
+                        },
+                        SyscallOutcome::Terminate => {
+                            pcb.state = ProcessState::Terminated;
+                            pcb.end_record_turnaround_time();
+                        },
+                    }
+                },
+                ProcessState::Running => {
+                    panic!("Process should not be set to running after being moved out of the CPU.");
+                },
+                ProcessState::Breakpoint => {
+                    panic!("Breakpoint hit on a CPU being driven by the scheduler's own dispatch loop, not a debugger.");
+                },
+            }
         }
 
-        // Notify all processes are finished if ready queue is empty.
-        if resources.ready_queue.is_empty() {
+        ShortTermScheduler::handle_interrupts(&mut resources, &cpu);
+
+        // Notify all processes are finished only once the ready queue is
+        // empty, nothing is parked waiting on I/O, and every core has gone
+        // idle -- if we only checked the queues, whichever core happened
+        // to empty them first could report the batch finished while
+        // another core was still mid-flight on its own process.
+        if resources.ready_queue.is_empty()
+            && resources.waiting_queue.is_empty()
+            && resources.current_pcbs.iter().all(Option::is_none)
+        {
             let (lock, condvar) = &*resources.all_procs_are_finished_condvar;
             let mut all_procs_are_finished = lock.lock().unwrap();
 
@@ -130,6 +367,37 @@ impl ShortTermScheduler {
             condvar.notify_all();
         }
     }
+
+    /// Drains the DMA channel's completed I/O requests and, for each one,
+    /// raises and claims an `IO_COMPLETE_IRQ` so the interrupt controller
+    /// still records the completion, then moves the matching process (by
+    /// id, since completions can arrive out of order relative to the
+    /// waiting queue) from the waiting queue back onto the ready queue.
+    fn handle_interrupts(resources: &mut ShortTermSchedulerResources, cpu: &Arc<Mutex<Cpu>>) {
+        let completed_process_ids = cpu.lock().unwrap().take_completed_io();
+
+        for process_id in completed_process_ids {
+            resources.interrupt_controller.raise(IO_COMPLETE_IRQ);
+
+            let irq = resources.interrupt_controller.claim().unwrap();
+
+            if let Some(pos) = resources.waiting_queue.iter()
+                .position(|pcb| pcb.lock().unwrap().get_id() == process_id) {
+                let pcb = resources.waiting_queue.remove(pos).unwrap();
+                let mut pcb_guard = pcb.lock().unwrap();
+
+                pcb_guard.end_record_io_wait_time();
+                pcb_guard.state = ProcessState::Ready;
+                pcb_guard.start_record_wait_time();
+                pcb_guard.mark_enqueued();
+                drop(pcb_guard);
+
+                resources.ready_queue.push(pcb);
+            }
+
+            resources.interrupt_controller.complete(irq);
+        }
+    }
 }
 
 impl Drop for ShortTermScheduler {
@@ -139,19 +407,39 @@ impl Drop for ShortTermScheduler {
 }
 
 struct ShortTermSchedulerResources {
-    cpu: Arc<Mutex<Cpu>>,
+    cpus: Vec<Arc<Mutex<Cpu>>>,
+    /// Needed only to run a trapped process's `write-output`/`read-input`
+    /// syscalls (see `syscall::handle_syscall`) against its own in/out
+    /// buffers -- every other use of memory stays inside `Cpu`.
+    memory: Arc<RwLock<Memory>>,
     ready_queue: Box<dyn SchedulerQueue + Send>,
-    current_pcb: Option<Arc<Mutex<ProcessControlBlock>>>,
+    waiting_queue: VecDeque<Arc<Mutex<ProcessControlBlock>>>,
+    interrupt_controller: InterruptController,
+    /// One slot per core in `cpus`: `Some` while that core has a process
+    /// actively dispatched to it, `None` once the core has gone idle. See
+    /// `ShortTermScheduler::dispatch`.
+    current_pcbs: Vec<Option<Arc<Mutex<ProcessControlBlock>>>>,
     all_procs_are_finished_condvar: Arc<(Mutex<bool>, Condvar)>,
+    /// Time slice, in instructions, each dispatched process is allotted
+    /// before being marked `Preempted` and put back on the ready queue.
+    /// `None` runs processes to
+    /// completion or I/O, matching `StsSchedulingAlg::Fifo`/`Priority`.
+    quantum: Option<usize>,
 }
 
 impl ShortTermSchedulerResources {
-    pub fn new(cpu: Arc<Mutex<Cpu>>, ready_queue: Box<dyn SchedulerQueue + Send>) -> ShortTermSchedulerResources {
+    pub fn new(cpus: Vec<Arc<Mutex<Cpu>>>, memory: Arc<RwLock<Memory>>, ready_queue: Box<dyn SchedulerQueue + Send>, quantum: Option<usize>) -> ShortTermSchedulerResources {
+        let num_cores = cpus.len();
+
         ShortTermSchedulerResources {
-            cpu,
+            cpus,
+            memory,
             ready_queue,
-            current_pcb: None,
+            waiting_queue: VecDeque::new(),
+            interrupt_controller: InterruptController::new(),
+            current_pcbs: vec![None; num_cores],
             all_procs_are_finished_condvar: Arc::new((Mutex::new(true), Condvar::new())),
+            quantum,
         }
     }
 }
@@ -160,6 +448,10 @@ trait SchedulerQueue {
     fn push(&mut self, pcb: Arc<Mutex<ProcessControlBlock>>);
     fn pop(&mut self) -> Option<Arc<Mutex<ProcessControlBlock>>>;
     fn is_empty(&self) -> bool;
+    /// Ids of every process currently sitting in the queue, in no
+    /// particular order. Used by `ShortTermScheduler::snapshot_queue_ids`
+    /// to checkpoint the queue's contents without disturbing it.
+    fn ids(&self) -> Vec<u32>;
 }
 
 struct FifoQueue {
@@ -186,27 +478,49 @@ impl SchedulerQueue for FifoQueue {
     fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    fn ids(&self) -> Vec<u32> {
+        self.queue.iter().map(|pcb| pcb.lock().unwrap().get_id()).collect()
+    }
 }
 
 struct PriorityQueue {
     queue: BinaryHeap<PriorityProcessControlBlock>,
+    aging_interval_ns: u128,
 }
 
 impl PriorityQueue {
-    pub fn new() -> PriorityQueue {
+    pub fn new(aging_interval_ns: u128) -> PriorityQueue {
         PriorityQueue {
             queue: BinaryHeap::new(),
+            aging_interval_ns,
         }
     }
+
+    /// `BinaryHeap` orders entries by the key they had at insertion time
+    /// and never re-heapifies when that key changes in place, but effective
+    /// priority rises the longer a process waits (see
+    /// `PriorityProcessControlBlock::effective_priority`). Rebuilding from
+    /// scratch against the current time on every push/pop keeps the heap
+    /// correctly ordered at the cost of redoing the comparisons, which is
+    /// cheap relative to a process's dispatch cost.
+    fn rebuild(&mut self) {
+        let aging_interval_ns = self.aging_interval_ns;
+
+        self.queue = self.queue.drain()
+            .map(|priority_pcb| PriorityProcessControlBlock::new(priority_pcb.pcb, aging_interval_ns))
+            .collect();
+    }
 }
 
 impl SchedulerQueue for PriorityQueue {
     fn push(&mut self, pcb: Arc<Mutex<ProcessControlBlock>>) {
-        let priority_pcb = PriorityProcessControlBlock::new(pcb);
-        self.queue.push(priority_pcb);
+        self.rebuild();
+        self.queue.push(PriorityProcessControlBlock::new(pcb, self.aging_interval_ns));
     }
 
     fn pop(&mut self) -> Option<Arc<Mutex<ProcessControlBlock>>> {
+        self.rebuild();
         let priority_pcb = self.queue.pop()?;
         Some(priority_pcb.pcb)
     }
@@ -214,23 +528,48 @@ impl SchedulerQueue for PriorityQueue {
     fn is_empty(&self) -> bool {
         self.queue.is_empty()
     }
+
+    fn ids(&self) -> Vec<u32> {
+        self.queue.iter().map(|priority_pcb| priority_pcb.pcb.lock().unwrap().get_id()).collect()
+    }
 }
 
 struct PriorityProcessControlBlock {
     pcb: Arc<Mutex<ProcessControlBlock>>,
+    aging_interval_ns: u128,
 }
 
 impl PriorityProcessControlBlock {
-    pub fn new(pcb: Arc<Mutex<ProcessControlBlock>>) -> PriorityProcessControlBlock {
+    pub fn new(pcb: Arc<Mutex<ProcessControlBlock>>, aging_interval_ns: u128) -> PriorityProcessControlBlock {
         PriorityProcessControlBlock {
             pcb,
+            aging_interval_ns,
         }
     }
+
+    /// Static priority plus one point for every `aging_interval_ns` spent
+    /// enqueued so far, so a process that has waited long enough
+    /// eventually outranks a fresher higher-priority one instead of
+    /// starving indefinitely. Aging is disabled when `aging_interval_ns`
+    /// is `0`.
+    fn effective_priority(&self) -> u64 {
+        let pcb = self.pcb.lock().unwrap();
+        let base_priority = pcb.get_priority() as u64;
+
+        if self.aging_interval_ns == 0 {
+            return base_priority;
+        }
+
+        let now_ns = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let waited_ns = now_ns.saturating_sub(pcb.get_enqueued_at_ns());
+
+        base_priority + (waited_ns / self.aging_interval_ns) as u64
+    }
 }
 
 impl PartialEq for PriorityProcessControlBlock {
     fn eq(&self, other: &Self) -> bool {
-        self.pcb.lock().unwrap().get_priority() == other.pcb.lock().unwrap().get_priority()
+        self.effective_priority() == other.effective_priority()
     }
 }
 
@@ -244,7 +583,7 @@ impl PartialOrd for PriorityProcessControlBlock {
 
 impl Ord for PriorityProcessControlBlock {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.pcb.lock().unwrap().get_priority().cmp(&other.pcb.lock().unwrap().get_priority())
+        self.effective_priority().cmp(&other.effective_priority())
     }
 }
 
@@ -252,6 +591,8 @@ impl Ord for PriorityProcessControlBlock {
 mod tests {
     use super::*;
 
+    use std::sync::RwLock;
+
     use crate::io::ProgramInfo;
 
     #[test]
@@ -288,7 +629,7 @@ mod tests {
         let pcb_2 = Arc::new(Mutex::new(ProcessControlBlock::new(&program_info_2, 0, 0)));
         let pcb_3 = Arc::new(Mutex::new(ProcessControlBlock::new(&program_info_3, 0, 0)));
 
-        let mut queue = PriorityQueue::new();
+        let mut queue = PriorityQueue::new(0);
         queue.push(pcb_2.clone());
         queue.push(pcb_1.clone());
         queue.push(pcb_3.clone());
@@ -297,4 +638,80 @@ mod tests {
         assert_eq!(queue.pop().unwrap().lock().unwrap().get_id(), 1);
         assert_eq!(queue.pop().unwrap().lock().unwrap().get_id(), 0);
     }
+
+    #[test]
+    fn test_priority_queue_aging_promotes_long_waiting_process() {
+        let low_priority_info = ProgramInfo {
+            id: 0,
+            priority: 1,
+            instruction_buffer_size: 0,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+        let high_priority_info = ProgramInfo {
+            id: 1,
+            priority: 5,
+            instruction_buffer_size: 0,
+            in_buffer_size: 0,
+            out_buffer_size: 0,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        };
+
+        let low_priority_pcb = Arc::new(Mutex::new(ProcessControlBlock::new(&low_priority_info, 0, 0)));
+        low_priority_pcb.lock().unwrap().mark_enqueued();
+
+        // A 1ms aging interval means the low-priority process's long wait
+        // below easily outweighs the high-priority process's 4-point head start.
+        let mut queue = PriorityQueue::new(1_000_000);
+        queue.push(low_priority_pcb.clone());
+
+        thread::sleep(std::time::Duration::from_millis(50));
+
+        let high_priority_pcb = Arc::new(Mutex::new(ProcessControlBlock::new(&high_priority_info, 0, 0)));
+        high_priority_pcb.lock().unwrap().mark_enqueued();
+        queue.push(high_priority_pcb.clone());
+
+        assert_eq!(queue.pop().unwrap().lock().unwrap().get_id(), 0);
+        assert_eq!(queue.pop().unwrap().lock().unwrap().get_id(), 1);
+    }
+
+    #[test]
+    fn test_short_term_scheduler_runs_processes_across_multiple_cores() {
+        let memory = Arc::new(RwLock::new(Memory::new()));
+
+        // Two tiny single-instruction (HLT) programs, one per core.
+        for id in 1..=2 {
+            let program_info = ProgramInfo {
+                id,
+                priority: 1,
+                instruction_buffer_size: 1,
+                in_buffer_size: 0,
+                out_buffer_size: 0,
+                temp_buffer_size: 0,
+                data_start_idx: 0,
+            };
+            let program_data: [u32; 1] = [0x92000000];
+
+            memory.write().unwrap().create_process(&program_info, &program_data);
+        }
+
+        let cpus = vec![
+            Arc::new(Mutex::new(Cpu::new(memory.clone()))),
+            Arc::new(Mutex::new(Cpu::new(memory.clone()))),
+        ];
+        let mut sts = ShortTermScheduler::new(cpus, memory.clone(), StsSchedulingAlg::Fifo);
+
+        let pcb_1 = memory.read().unwrap().get_pcb_for(1);
+        let pcb_2 = memory.read().unwrap().get_pcb_for(2);
+
+        sts.schedule_process(pcb_1.clone());
+        sts.schedule_process(pcb_2.clone());
+        sts.await_all_procs_finished();
+
+        assert!(matches!(pcb_1.lock().unwrap().state, ProcessState::Terminated));
+        assert!(matches!(pcb_2.lock().unwrap().state, ProcessState::Terminated));
+    }
 }
\ No newline at end of file
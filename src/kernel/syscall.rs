@@ -0,0 +1,198 @@
+use super::cpu::PendingSyscall;
+use super::{Memory, ProcessControlBlock};
+
+/// Service numbers a process passes in the register named by `TRAP Rn`
+/// (see `cpu::execute_trap`); anything else is `Exception::Unknown`.
+const SERVICE_EXIT: u32 = 0;
+const SERVICE_YIELD: u32 = 1;
+const SERVICE_WRITE_OUTPUT: u32 = 2;
+const SERVICE_READ_INPUT: u32 = 3;
+const SERVICE_PRINT_ERROR: u32 = 4;
+
+/// A decoded `TRAP` request: which kernel service `PendingSyscall::number`
+/// named, plus whatever register arguments that service needs, read out
+/// of `PendingSyscall::registers` by convention -- R2 holds a buffer index
+/// (`WriteOutput`/`ReadInput`) or a message word (`PrintError`), and R3
+/// holds `WriteOutput`'s value, the same way `execute_st`/`execute_lw`
+/// hard-code R0 as the never-indirected accumulator. This is the boundary
+/// `Cpu::execute_trap` raises a software interrupt across: a process only
+/// ever sees the trap and its resumed result, never `handle_syscall`
+/// itself running in between.
+pub(crate) enum Exception {
+    /// End this process instead of running to `HLT`. Its PCB is marked
+    /// `Terminated` but stays resident until the long-term scheduler
+    /// unloads the batch, the same as after `HLT`.
+    Exit,
+    /// Cooperatively give up the rest of this quantum and go back to the
+    /// ready queue, the same way a quantum timeout does.
+    Yield,
+    /// Write `value` into the out buffer, `index` words past its start.
+    WriteOutput { index: usize, value: u32 },
+    /// Read the in buffer, `index` words past its start, into `dest_reg`.
+    ReadInput { index: usize, dest_reg: usize },
+    /// Print `message` as a diagnostic without tearing the process down.
+    PrintError { message: u32 },
+    /// A service number `Cpu::execute_trap` didn't recognize.
+    Unknown(u32),
+}
+
+impl Exception {
+    pub fn from_pending_syscall(pending: &PendingSyscall) -> Exception {
+        match pending.number {
+            SERVICE_EXIT => Exception::Exit,
+            SERVICE_YIELD => Exception::Yield,
+            SERVICE_WRITE_OUTPUT => Exception::WriteOutput {
+                index: pending.registers[2] as usize,
+                value: pending.registers[3],
+            },
+            SERVICE_READ_INPUT => Exception::ReadInput {
+                index: pending.registers[2] as usize,
+                dest_reg: 2,
+            },
+            SERVICE_PRINT_ERROR => Exception::PrintError { message: pending.registers[2] },
+            other => Exception::Unknown(other),
+        }
+    }
+}
+
+/// What `handle_syscall` tells the scheduler to do with the process that
+/// issued the trap, once the service it asked for has run.
+pub(crate) enum SyscallOutcome {
+    /// Put the process back in the ready queue to resume where it trapped.
+    Continue,
+    /// The process asked to end; tear it down like a `HLT`.
+    Terminate,
+}
+
+/// Runs one decoded `Exception` against `pcb`/`memory` -- the kernel-side
+/// half of the supervisor-call boundary a `TRAP` opens. Doesn't touch
+/// `pcb.program_counter`/`registers` beyond what a service like
+/// `ReadInput` writes back. When another process is queued for the core,
+/// `Cpu::execute_process` has already written the rest of the trapped
+/// state into `pcb` before the scheduler got here; otherwise the process
+/// resumes straight off the CPU's own state, and the scheduler copies
+/// `ReadInput`'s result back into it (see `ShortTermScheduler::dispatch`).
+/// Bounds-checks `WriteOutput`/
+/// `ReadInput` against `pcb`'s own buffer getters and goes through
+/// `Memory::read_from`/`write_to` rather than `read_for`/`write_for` --
+/// the latter re-derive the PCB from `process_id` and lock it again,
+/// which would deadlock against the lock the caller is already holding
+/// on this same `pcb` (see `ShortTermScheduler::dispatch`).
+pub(crate) fn handle_syscall(pcb: &mut ProcessControlBlock, memory: &mut Memory, exception: Exception) -> SyscallOutcome {
+    match exception {
+        Exception::Exit => SyscallOutcome::Terminate,
+        Exception::Yield => SyscallOutcome::Continue,
+        Exception::WriteOutput { index, value } => {
+            let out_start = pcb.get_mem_out_start_address();
+            let out_end = pcb.get_mem_temp_start_address();
+            let address = out_start + index;
+
+            if address >= out_end {
+                eprintln!("Process {} write-output syscall out of bounds: index {} into a {}-word out buffer", pcb.get_id(), index, out_end - out_start);
+            } else {
+                memory.write_to(address, value);
+            }
+
+            SyscallOutcome::Continue
+        },
+        Exception::ReadInput { index, dest_reg } => {
+            let in_start = pcb.get_mem_in_start_address();
+            let in_end = pcb.get_mem_out_start_address();
+            let address = in_start + index;
+
+            if address >= in_end {
+                eprintln!("Process {} read-input syscall out of bounds: index {} into a {}-word in buffer", pcb.get_id(), index, in_end - in_start);
+            } else {
+                pcb.registers[dest_reg] = memory.read_from(address);
+            }
+
+            SyscallOutcome::Continue
+        },
+        Exception::PrintError { message } => {
+            eprintln!("Process {} diagnostic: {:#010x}", pcb.get_id(), message);
+            SyscallOutcome::Continue
+        },
+        Exception::Unknown(number) => {
+            eprintln!("Process {} issued unknown syscall {}; ignoring.", pcb.get_id(), number);
+            SyscallOutcome::Continue
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::io::ProgramInfo;
+
+    fn make_program_info(id: u32) -> ProgramInfo {
+        ProgramInfo {
+            id,
+            priority: 1,
+            instruction_buffer_size: 1,
+            in_buffer_size: 1,
+            out_buffer_size: 1,
+            temp_buffer_size: 0,
+            data_start_idx: 0,
+        }
+    }
+
+    #[test]
+    fn test_from_pending_syscall_decodes_each_known_service() {
+        let with_number_and_args = |number: u32, a2: u32, a3: u32| {
+            let mut registers = [0; 16];
+            registers[2] = a2;
+            registers[3] = a3;
+            PendingSyscall { number, registers }
+        };
+
+        assert!(matches!(Exception::from_pending_syscall(&with_number_and_args(0, 0, 0)), Exception::Exit));
+        assert!(matches!(Exception::from_pending_syscall(&with_number_and_args(1, 0, 0)), Exception::Yield));
+        assert!(matches!(
+            Exception::from_pending_syscall(&with_number_and_args(2, 5, 0x99)),
+            Exception::WriteOutput { index: 5, value: 0x99 },
+        ));
+        assert!(matches!(
+            Exception::from_pending_syscall(&with_number_and_args(3, 0, 0)),
+            Exception::ReadInput { index: 0, dest_reg: 2 },
+        ));
+        assert!(matches!(Exception::from_pending_syscall(&with_number_and_args(4, 0xBAD, 0)), Exception::PrintError { message: 0xBAD }));
+        assert!(matches!(Exception::from_pending_syscall(&with_number_and_args(99, 0, 0)), Exception::Unknown(99)));
+    }
+
+    #[test]
+    fn test_handle_syscall_exit_tells_caller_to_terminate() {
+        let mut memory = Memory::new();
+        memory.create_process(&make_program_info(1), &[0x92000000]);
+        let pcb = memory.get_pcb_for(1);
+        let mut pcb = pcb.lock().unwrap();
+
+        assert!(matches!(handle_syscall(&mut pcb, &mut memory, Exception::Exit), SyscallOutcome::Terminate));
+    }
+
+    #[test]
+    fn test_handle_syscall_write_output_then_read_input_round_trip_through_memory() {
+        let mut memory = Memory::new();
+        memory.create_process(&make_program_info(1), &[0x92000000, 0, 0]);
+        let pcb_handle = memory.get_pcb_for(1);
+
+        {
+            let mut pcb = pcb_handle.lock().unwrap();
+            let outcome = handle_syscall(&mut pcb, &mut memory, Exception::WriteOutput { index: 0, value: 0x42 });
+            assert!(matches!(outcome, SyscallOutcome::Continue));
+        }
+
+        let out_address = pcb_handle.lock().unwrap().get_mem_out_start_address();
+        assert_eq!(memory.read_from(out_address), 0x42);
+
+        // Copy the value we just wrote into the in buffer, then read it
+        // back through the syscall into R2.
+        let in_address = pcb_handle.lock().unwrap().get_mem_in_start_address();
+        memory.write_to(in_address, 0x42);
+
+        let mut pcb = pcb_handle.lock().unwrap();
+        let outcome = handle_syscall(&mut pcb, &mut memory, Exception::ReadInput { index: 0, dest_reg: 2 });
+        assert!(matches!(outcome, SyscallOutcome::Continue));
+        assert_eq!(pcb.registers[2], 0x42);
+    }
+}
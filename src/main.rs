@@ -4,6 +4,16 @@ mod kernel;
 use kernel::Driver;
 
 fn main() {
-    let mut _driver = Driver::new();
-    _driver.start();
-}
\ No newline at end of file
+    let restore_path = std::env::args().skip_while(|arg| arg != "--restore").nth(1);
+
+    match restore_path {
+        Some(path) => match Driver::restore(&path) {
+            Ok(mut driver) => driver.resume(),
+            Err(err) => {
+                println!("Failed to restore from checkpoint '{}', starting fresh: {}", path, err);
+                Driver::new().start();
+            },
+        },
+        None => Driver::new().start(),
+    };
+}